@@ -0,0 +1,97 @@
+//! Contract kinds compiled into this build of the crate.
+//!
+//! The finance contracts are always available; the storage examples depend on
+//! their feature flags.
+
+/// Declares [`ContractKind`] from one table of `Variant => feature` rows, so
+/// the feature name reported by [`ContractKind::feature`] and the `cfg!` behind
+/// [`ContractKind::is_enabled`] come from the same literal.
+///
+/// A misspelled feature would make `cfg!` silently false; `unexpected_cfgs` is
+/// denied so any name not declared in the crate manifest fails the build.
+macro_rules! contract_kinds {
+    ($($kind:ident => $feature:tt,)+) => {
+        /// A contract template shipped by this crate.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum ContractKind {
+            $($kind,)+
+        }
+
+        impl ContractKind {
+            pub const ALL: [Self; [$(Self::$kind),+].len()] = [$(Self::$kind),+];
+
+            /// Cargo feature gating this contract, or `None` if it is always built.
+            #[must_use]
+            pub const fn feature(self) -> Option<&'static str> {
+                match self {
+                    $(Self::$kind => contract_kinds!(@name $feature),)+
+                }
+            }
+
+            /// Whether this contract was compiled into the crate.
+            #[must_use]
+            #[deny(unexpected_cfgs)]
+            pub const fn is_enabled(self) -> bool {
+                match self {
+                    $(Self::$kind => contract_kinds!(@enabled $feature),)+
+                }
+            }
+        }
+    };
+    (@name always) => {
+        None
+    };
+    (@name $feature:literal) => {
+        Some($feature)
+    };
+    (@enabled always) => {
+        true
+    };
+    (@enabled $feature:literal) => {
+        cfg!(feature = $feature)
+    };
+}
+
+contract_kinds! {
+    Options => always,
+    OptionOffer => always,
+    SimpleStorage => "simple-storage",
+    Bytes32TrStorage => "bytes32-tr-storage",
+    ArrayTrStorage => "array-tr-storage",
+}
+
+impl ContractKind {
+    /// Contracts compiled into the crate, in [`ContractKind::ALL`] order.
+    #[must_use]
+    pub fn enabled() -> Vec<Self> {
+        Self::ALL
+            .into_iter()
+            .filter(|kind| kind.is_enabled())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod features_tests {
+    use super::*;
+
+    #[test]
+    fn test_enabled_matches_feature_flags() {
+        let enabled = ContractKind::enabled();
+
+        assert!(enabled.contains(&ContractKind::Options));
+        assert!(enabled.contains(&ContractKind::OptionOffer));
+        assert_eq!(
+            enabled.contains(&ContractKind::SimpleStorage),
+            cfg!(feature = "simple-storage")
+        );
+        assert_eq!(
+            enabled.contains(&ContractKind::Bytes32TrStorage),
+            cfg!(feature = "bytes32-tr-storage")
+        );
+        assert_eq!(
+            enabled.contains(&ContractKind::ArrayTrStorage),
+            cfg!(feature = "array-tr-storage")
+        );
+    }
+}
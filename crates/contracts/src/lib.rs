@@ -5,10 +5,12 @@
 //! - [`state_management`]: standalone storage contract examples, each behind a
 //!   feature flag.
 //! - [`simplicityhl_core`]: shared compile/execute/taproot helpers.
+//! - [`features`]: which contract kinds this build includes.
 #![warn(clippy::all, clippy::pedantic)]
 #[rustfmt::skip]
 #[allow(clippy::all, clippy::pedantic, clippy::nursery, clippy::cargo)]
 pub mod artifacts;
+pub mod features;
 pub mod programs;
 pub mod simplicityhl_core;
 pub mod state_management;
@@ -1,5 +1,23 @@
 //! Finance contract wrappers around the Simplex-generated program artifacts.
+//!
+//! # Internal keys
+//!
+//! Each contract address is a taproot output with the contract program as its
+//! only script leaf. `new` uses the BIP-0341 unspendable internal key, while
+//! `from_internal_key` takes any other. Whoever can sign for the internal key
+//! can spend through the key path and skip every contract branch, so a custom
+//! key must be unspendable as well, for example one from
+//! [`tweaked_unspendable_key`](crate::scripts::tweaked_unspendable_key).
+//!
+//! Changing the internal key changes the address but not the terms. No
+//! program has a branch that moves funds to another address under the same
+//! terms, so a transaction migrating a funded covenant to a new internal key
+//! cannot be built. Funds already locked can only leave through the contract
+//! branches.
 
 pub mod option_offer;
 pub mod options;
 pub mod program;
+
+#[cfg(test)]
+mod simulation;
@@ -5,13 +5,50 @@ use crate::artifacts::option_offer::OptionOfferProgram;
 use crate::artifacts::option_offer::derived_option_offer::{
     OptionOfferArguments, OptionOfferWitness,
 };
+use crate::error::ProgramError;
 use crate::programs::program::SimplexProgram;
+use crate::runner::compute_sighash_all;
+
+use std::str::FromStr;
 
 use simplex::constants::DUMMY_SIGNATURE;
 use simplex::either::{Left, Right};
 use simplex::program::Program;
 use simplex::provider::SimplicityNetwork;
+use simplex::simplicityhl::elements::pset::PartiallySignedTransaction;
 use simplex::simplicityhl::elements::{AssetId, secp256k1_zkp::XOnlyPublicKey};
+use simplex::simplicityhl::simplicity::bitcoin::key::Keypair;
+
+/// Errors returned when option offer parameters fail validation.
+#[derive(Debug, thiserror::Error)]
+pub enum OptionOfferError {
+    #[error("Invalid {field} asset id '{value}': {reason}")]
+    InvalidAssetId {
+        field: &'static str,
+        value: String,
+        reason: String,
+    },
+
+    /// Returned when two of the collateral, premium, and settlement assets coincide.
+    #[error("{first} and {second} asset ids must be distinct, both are {asset_id}")]
+    DuplicateAssetId {
+        first: &'static str,
+        second: &'static str,
+        asset_id: AssetId,
+    },
+
+    /// Returned when a user branch is signed with a key other than `user_pubkey`.
+    ///
+    /// The keys are boxed to keep the error small.
+    #[error("Signing key {actual} does not match the offer user key {expected}")]
+    UserKeyMismatch {
+        expected: Box<XOnlyPublicKey>,
+        actual: Box<XOnlyPublicKey>,
+    },
+
+    #[error(transparent)]
+    Program(#[from] ProgramError),
+}
 
 #[derive(Debug, Clone, Copy)]
 pub struct OptionOfferParameters {
@@ -25,6 +62,85 @@ pub struct OptionOfferParameters {
     pub network: SimplicityNetwork,
 }
 
+impl OptionOfferParameters {
+    /// Build parameters from hex-encoded asset ids and validate them.
+    ///
+    /// # Errors
+    /// Returns an error naming the offending field if an asset id is malformed
+    /// or if the asset ids are not pairwise distinct.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_from_hex(
+        collateral_asset_id: &str,
+        premium_asset_id: &str,
+        settlement_asset_id: &str,
+        collateral_per_contract: u64,
+        premium_per_collateral: u64,
+        expiry_time: u32,
+        user_pubkey: XOnlyPublicKey,
+        network: SimplicityNetwork,
+    ) -> Result<Self, OptionOfferError> {
+        let parameters = Self {
+            collateral_asset_id: parse_asset_id("collateral", collateral_asset_id)?,
+            premium_asset_id: parse_asset_id("premium", premium_asset_id)?,
+            settlement_asset_id: parse_asset_id("settlement", settlement_asset_id)?,
+            collateral_per_contract,
+            premium_per_collateral,
+            expiry_time,
+            user_pubkey,
+            network,
+        };
+        parameters.validate()?;
+
+        Ok(parameters)
+    }
+
+    /// Check that the collateral, premium, and settlement assets are pairwise distinct.
+    ///
+    /// # Errors
+    /// Returns [`OptionOfferError::DuplicateAssetId`] for the first pair that matches.
+    pub fn validate(&self) -> Result<(), OptionOfferError> {
+        let assets = [
+            ("collateral", self.collateral_asset_id),
+            ("premium", self.premium_asset_id),
+            ("settlement", self.settlement_asset_id),
+        ];
+
+        for (index, &(first, asset_id)) in assets.iter().enumerate() {
+            if let Some(&(second, _)) = assets[index + 1..].iter().find(|(_, id)| *id == asset_id) {
+                return Err(OptionOfferError::DuplicateAssetId {
+                    first,
+                    second,
+                    asset_id,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Premium released to a taker exercising `collateral_amount`, or `None`
+    /// on overflow.
+    #[must_use]
+    pub const fn required_premium(&self, collateral_amount: u64) -> Option<u64> {
+        collateral_amount.checked_mul(self.premium_per_collateral)
+    }
+
+    /// Settlement a taker must pay to exercise `collateral_amount`, or `None`
+    /// on overflow.
+    #[must_use]
+    pub const fn required_settlement(&self, collateral_amount: u64) -> Option<u64> {
+        collateral_amount.checked_mul(self.collateral_per_contract)
+    }
+}
+
+fn parse_asset_id(field: &'static str, value: &str) -> Result<AssetId, OptionOfferError> {
+    AssetId::from_str(value).map_err(|error| OptionOfferError::InvalidAssetId {
+        field,
+        value: value.to_string(),
+        reason: error.to_string(),
+    })
+}
+
 impl From<OptionOfferParameters> for OptionOfferArguments {
     fn from(value: OptionOfferParameters) -> Self {
         Self {
@@ -54,7 +170,12 @@ pub enum OptionOfferBranch {
         is_change_needed: bool,
     },
     /// Withdraw path: user withdraws settlement asset
-    Withdraw,
+    Withdraw {
+        /// Amount of settlement asset paid out to the user
+        amount: u64,
+        /// Whether the remainder returns to the covenant (partial withdrawal)
+        is_change_needed: bool,
+    },
     /// Expiry path: user reclaims collateral + premium after expiry
     Expiry,
 }
@@ -68,6 +189,11 @@ impl OptionOffer {
         }
     }
 
+    /// Build the offer covenant with `internal_key` as its taproot internal
+    /// key instead of the BIP-0341 unspendable key.
+    ///
+    /// Whoever can sign for `internal_key` can take the offer through the key
+    /// path, so it must be unspendable, see [`crate::programs`].
     #[must_use]
     pub fn from_internal_key(
         internal_key: XOnlyPublicKey,
@@ -99,7 +225,10 @@ impl OptionOffer {
                 collateral_amount,
                 is_change_needed,
             } => Left((collateral_amount, is_change_needed)),
-            OptionOfferBranch::Withdraw => Right(Left(())),
+            OptionOfferBranch::Withdraw {
+                amount,
+                is_change_needed,
+            } => Right(Left((amount, is_change_needed))),
             OptionOfferBranch::Expiry => Right(Right(())),
         };
 
@@ -108,6 +237,43 @@ impl OptionOffer {
             path,
         }
     }
+
+    /// Build the witness for `option_offer_branch` spending input
+    /// `input_index` of `pst`, signing its `SIGHASH_ALL` digest with the user
+    /// key where the branch requires it.
+    ///
+    /// `Exercise` is permissionless, so its witness keeps the dummy signature
+    /// and `user_keypair` is not used.
+    ///
+    /// # Errors
+    /// Returns [`OptionOfferError::UserKeyMismatch`] if a signing branch is
+    /// given a keypair other than the one for `parameters.user_pubkey`, or an
+    /// error if the digest cannot be computed, see [`compute_sighash_all`].
+    pub fn get_signed_witness(
+        &self,
+        option_offer_branch: OptionOfferBranch,
+        pst: &PartiallySignedTransaction,
+        input_index: usize,
+        user_keypair: &Keypair,
+    ) -> Result<OptionOfferWitness, OptionOfferError> {
+        let mut witness = Self::get_witness(option_offer_branch);
+
+        if !matches!(option_offer_branch, OptionOfferBranch::Exercise { .. }) {
+            let (signing_pubkey, _) = user_keypair.x_only_public_key();
+            if signing_pubkey != self.parameters.user_pubkey {
+                return Err(OptionOfferError::UserKeyMismatch {
+                    expected: Box::new(self.parameters.user_pubkey),
+                    actual: Box::new(signing_pubkey),
+                });
+            }
+
+            let sighash_all =
+                compute_sighash_all(self.get_program(), pst, input_index, self.get_network())?;
+            witness.user_sighash_all = user_keypair.sign_schnorr(sighash_all).serialize();
+        }
+
+        Ok(witness)
+    }
 }
 
 impl SimplexProgram for OptionOffer {
@@ -119,3 +285,600 @@ impl SimplexProgram for OptionOffer {
         &self.parameters.network
     }
 }
+
+#[cfg(test)]
+mod option_offer_tests {
+    use super::*;
+
+    use crate::programs::simulation::Destination::{Covenant, Receiver};
+    use crate::programs::simulation::{
+        CompiledCovenant, Destination, SimulatedSpend, assert_spend_outcome,
+    };
+    use crate::scripts::{tweaked_unspendable_key, unspendable_internal_key};
+
+    use std::sync::Arc;
+
+    use proptest::prelude::*;
+    use simplex::program::{ArgumentsTrait, WitnessTrait};
+    use simplex::simplicityhl::elements::Transaction;
+    use simplex::simplicityhl::elements::pset::Input;
+    use simplex::simplicityhl::elements::{LockTime, OutPoint};
+    use simplex::simplicityhl::simplicity::bitcoin::secp256k1;
+    use simplex::simplicityhl::simplicity::hashes::Hash as _;
+    use simplex::simplicityhl::simplicity::jet::elements::ElementsEnv;
+
+    const COLLATERAL_HEX: &str = "144c654344aa716d6f3abcc1ca90e5641e4e2a7f633bc09fe3baf64585819a49";
+    const PREMIUM_HEX: &str = "1111111111111111111111111111111111111111111111111111111111111111";
+    const SETTLEMENT_HEX: &str = "2222222222222222222222222222222222222222222222222222222222222222";
+
+    const EXPIRY_TIME: u32 = 1_700_000_000;
+
+    fn user_keypair() -> Keypair {
+        let secret_key = secp256k1::SecretKey::from_slice(&[1u8; 32]).expect("valid secret key");
+        Keypair::from_secret_key(secp256k1::SECP256K1, &secret_key)
+    }
+
+    fn user_pubkey() -> XOnlyPublicKey {
+        user_keypair().x_only_public_key().0
+    }
+
+    fn parameters_from_hex(
+        collateral: &str,
+        premium: &str,
+        settlement: &str,
+    ) -> Result<OptionOfferParameters, OptionOfferError> {
+        OptionOfferParameters::try_from_hex(
+            collateral,
+            premium,
+            settlement,
+            10,
+            10,
+            EXPIRY_TIME,
+            user_pubkey(),
+            SimplicityNetwork::LiquidTestnet,
+        )
+    }
+
+    #[test]
+    fn test_try_from_hex_accepts_distinct_assets() {
+        let parameters = parameters_from_hex(COLLATERAL_HEX, PREMIUM_HEX, SETTLEMENT_HEX)
+            .expect("distinct asset ids should be accepted");
+
+        assert_eq!(parameters.collateral_asset_id.to_string(), COLLATERAL_HEX);
+        assert_eq!(parameters.premium_asset_id.to_string(), PREMIUM_HEX);
+        assert_eq!(parameters.settlement_asset_id.to_string(), SETTLEMENT_HEX);
+    }
+
+    #[test]
+    fn test_try_from_hex_rejects_malformed_asset_id() {
+        let error = parameters_from_hex(COLLATERAL_HEX, "not-hex", SETTLEMENT_HEX)
+            .expect_err("malformed premium asset id should be rejected");
+
+        assert!(matches!(
+            error,
+            OptionOfferError::InvalidAssetId {
+                field: "premium",
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_try_from_hex_rejects_duplicate_asset_ids() {
+        let error = parameters_from_hex(COLLATERAL_HEX, PREMIUM_HEX, COLLATERAL_HEX)
+            .expect_err("duplicate asset ids should be rejected");
+
+        assert!(matches!(
+            error,
+            OptionOfferError::DuplicateAssetId {
+                first: "collateral",
+                second: "settlement",
+                ..
+            }
+        ));
+    }
+
+    /// A hand-built spend of the offer covenant and whether the branch
+    /// should accept it.
+    struct BranchCase {
+        name: &'static str,
+        branch: OptionOfferBranch,
+        spend: SimulatedSpend,
+        should_succeed: bool,
+    }
+
+    fn compile_offer(option_offer: &OptionOffer) -> CompiledCovenant {
+        CompiledCovenant::new(
+            option_offer,
+            OptionOfferProgram::SOURCE,
+            OptionOfferArguments::from(option_offer.parameters).build_arguments(),
+        )
+    }
+
+    /// Execute `case` on every covenant input, building each input's witness
+    /// with `witness`.
+    fn simulate_branch(
+        covenant: &CompiledCovenant,
+        case: &BranchCase,
+        witness: impl Fn(
+            &PartiallySignedTransaction,
+            usize,
+            &ElementsEnv<Arc<Transaction>>,
+        ) -> OptionOfferWitness,
+    ) -> Result<(), ProgramError> {
+        covenant.execute_spend(&case.spend, |pst, input_index, env| {
+            witness(pst, input_index, env).build_witness()
+        })
+    }
+
+    /// The witness [`OptionOffer::get_signed_witness`] builds, signed by
+    /// `keypair` over the digest already in `env`.
+    fn signed_with(
+        branch: OptionOfferBranch,
+        env: &ElementsEnv<Arc<Transaction>>,
+        keypair: &Keypair,
+    ) -> OptionOfferWitness {
+        let mut witness = OptionOffer::get_witness(branch);
+        if !matches!(branch, OptionOfferBranch::Exercise { .. }) {
+            let sighash_all =
+                secp256k1::Message::from_digest(*env.c_tx_env().sighash_all().as_byte_array());
+            witness.user_sighash_all = keypair.sign_schnorr(sighash_all).serialize();
+        }
+        witness
+    }
+
+    fn spend(
+        lock_time: LockTime,
+        covenant_inputs: Vec<(AssetId, u64)>,
+        outputs: Vec<(Destination, AssetId, u64)>,
+    ) -> SimulatedSpend {
+        SimulatedSpend {
+            lock_time,
+            covenant_inputs,
+            other_inputs: Vec::new(),
+            outputs,
+        }
+    }
+
+    /// Cover every offer branch, accepting and rejecting, on hand-built
+    /// spends so the full branch set runs without a node.
+    fn all_branch_cases(parameters: &OptionOfferParameters) -> Vec<BranchCase> {
+        let collateral = parameters.collateral_asset_id;
+        let premium = parameters.premium_asset_id;
+        let settlement = parameters.settlement_asset_id;
+        let after_expiry = LockTime::from_time(EXPIRY_TIME).expect("valid time lock");
+        let before_expiry = LockTime::from_time(EXPIRY_TIME - 1).expect("valid time lock");
+
+        vec![
+            BranchCase {
+                name: "exercise full",
+                branch: OptionOfferBranch::Exercise {
+                    collateral_amount: 100,
+                    is_change_needed: false,
+                },
+                spend: spend(
+                    LockTime::ZERO,
+                    vec![(collateral, 100), (premium, 1_000)],
+                    vec![
+                        (Covenant, settlement, 1_000),
+                        (Receiver, collateral, 100),
+                        (Receiver, premium, 1_000),
+                    ],
+                ),
+                should_succeed: true,
+            },
+            BranchCase {
+                name: "exercise with change",
+                branch: OptionOfferBranch::Exercise {
+                    collateral_amount: 60,
+                    is_change_needed: true,
+                },
+                spend: spend(
+                    LockTime::ZERO,
+                    vec![(collateral, 100), (premium, 1_000)],
+                    vec![
+                        (Covenant, collateral, 40),
+                        (Covenant, premium, 400),
+                        (Covenant, settlement, 600),
+                        (Receiver, collateral, 60),
+                        (Receiver, premium, 600),
+                    ],
+                ),
+                should_succeed: true,
+            },
+            BranchCase {
+                name: "exercise underpaying settlement",
+                branch: OptionOfferBranch::Exercise {
+                    collateral_amount: 100,
+                    is_change_needed: false,
+                },
+                spend: spend(
+                    LockTime::ZERO,
+                    vec![(collateral, 100), (premium, 1_000)],
+                    vec![
+                        (Covenant, settlement, 999),
+                        (Receiver, collateral, 100),
+                        (Receiver, premium, 1_000),
+                    ],
+                ),
+                should_succeed: false,
+            },
+            BranchCase {
+                name: "exercise paying settlement to the receiver",
+                branch: OptionOfferBranch::Exercise {
+                    collateral_amount: 100,
+                    is_change_needed: false,
+                },
+                spend: spend(
+                    LockTime::ZERO,
+                    vec![(collateral, 100), (premium, 1_000)],
+                    vec![
+                        (Receiver, settlement, 1_000),
+                        (Receiver, collateral, 100),
+                        (Receiver, premium, 1_000),
+                    ],
+                ),
+                should_succeed: false,
+            },
+            BranchCase {
+                name: "withdraw full",
+                branch: OptionOfferBranch::Withdraw {
+                    amount: 1_000,
+                    is_change_needed: false,
+                },
+                spend: spend(
+                    LockTime::ZERO,
+                    vec![(settlement, 1_000)],
+                    vec![(Receiver, settlement, 1_000)],
+                ),
+                should_succeed: true,
+            },
+            BranchCase {
+                name: "withdraw partially",
+                branch: OptionOfferBranch::Withdraw {
+                    amount: 400,
+                    is_change_needed: true,
+                },
+                spend: spend(
+                    LockTime::ZERO,
+                    vec![(settlement, 1_000)],
+                    vec![(Receiver, settlement, 400), (Covenant, settlement, 600)],
+                ),
+                should_succeed: true,
+            },
+            BranchCase {
+                name: "withdraw partially without returning change",
+                branch: OptionOfferBranch::Withdraw {
+                    amount: 400,
+                    is_change_needed: true,
+                },
+                spend: spend(
+                    LockTime::ZERO,
+                    vec![(settlement, 1_000)],
+                    vec![(Receiver, settlement, 400), (Receiver, settlement, 600)],
+                ),
+                should_succeed: false,
+            },
+            BranchCase {
+                name: "withdraw of nothing",
+                branch: OptionOfferBranch::Withdraw {
+                    amount: 0,
+                    is_change_needed: true,
+                },
+                spend: spend(
+                    LockTime::ZERO,
+                    vec![(settlement, 1_000)],
+                    vec![(Receiver, settlement, 0), (Covenant, settlement, 1_000)],
+                ),
+                should_succeed: false,
+            },
+            BranchCase {
+                name: "withdraw partially leaving no change",
+                branch: OptionOfferBranch::Withdraw {
+                    amount: 1_000,
+                    is_change_needed: true,
+                },
+                spend: spend(
+                    LockTime::ZERO,
+                    vec![(settlement, 1_000)],
+                    vec![(Receiver, settlement, 1_000), (Covenant, settlement, 0)],
+                ),
+                should_succeed: false,
+            },
+            BranchCase {
+                name: "withdraw of the wrong asset",
+                branch: OptionOfferBranch::Withdraw {
+                    amount: 100,
+                    is_change_needed: false,
+                },
+                spend: spend(
+                    LockTime::ZERO,
+                    vec![(collateral, 100)],
+                    vec![(Receiver, collateral, 100)],
+                ),
+                should_succeed: false,
+            },
+            BranchCase {
+                name: "expiry",
+                branch: OptionOfferBranch::Expiry,
+                spend: spend(
+                    after_expiry,
+                    vec![(collateral, 100), (premium, 1_000)],
+                    vec![(Receiver, collateral, 100), (Receiver, premium, 1_000)],
+                ),
+                should_succeed: true,
+            },
+            BranchCase {
+                name: "expiry before expiry time",
+                branch: OptionOfferBranch::Expiry,
+                spend: spend(
+                    before_expiry,
+                    vec![(collateral, 100), (premium, 1_000)],
+                    vec![(Receiver, collateral, 100), (Receiver, premium, 1_000)],
+                ),
+                should_succeed: false,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_simulate_all_branches() {
+        let parameters = parameters_from_hex(COLLATERAL_HEX, PREMIUM_HEX, SETTLEMENT_HEX)
+            .expect("distinct asset ids should be accepted");
+        let covenant = compile_offer(&OptionOffer::new(parameters));
+
+        for case in all_branch_cases(&parameters) {
+            let result = simulate_branch(&covenant, &case, |_, _, env| {
+                signed_with(case.branch, env, &user_keypair())
+            });
+            assert_spend_outcome(case.name, &result, case.should_succeed);
+        }
+    }
+
+    #[test]
+    fn test_signed_witness_requires_user_key() {
+        let parameters = parameters_from_hex(COLLATERAL_HEX, PREMIUM_HEX, SETTLEMENT_HEX)
+            .expect("distinct asset ids should be accepted");
+        let option_offer = OptionOffer::new(parameters);
+        let covenant = compile_offer(&option_offer);
+        let withdraw_case = |name, should_succeed| BranchCase {
+            name,
+            branch: OptionOfferBranch::Withdraw {
+                amount: 1_000,
+                is_change_needed: false,
+            },
+            spend: spend(
+                LockTime::ZERO,
+                vec![(parameters.settlement_asset_id, 1_000)],
+                vec![(Receiver, parameters.settlement_asset_id, 1_000)],
+            ),
+            should_succeed,
+        };
+
+        let secret_key = secp256k1::SecretKey::from_slice(&[2u8; 32]).expect("valid secret key");
+        let other_keypair = Keypair::from_secret_key(secp256k1::SECP256K1, &secret_key);
+
+        let case = withdraw_case("withdraw signed by the user key", true);
+        let result = simulate_branch(&covenant, &case, |pst, input_index, _| {
+            option_offer
+                .get_signed_witness(case.branch, pst, input_index, &user_keypair())
+                .expect("user keypair matches the offer")
+        });
+        assert_spend_outcome(case.name, &result, case.should_succeed);
+
+        let case = withdraw_case("withdraw signed by another key", false);
+        let result = simulate_branch(&covenant, &case, |pst, input_index, env| {
+            let error = option_offer
+                .get_signed_witness(case.branch, pst, input_index, &other_keypair)
+                .expect_err("other keypair does not match the offer");
+            assert!(matches!(
+                error,
+                OptionOfferError::UserKeyMismatch { expected, actual }
+                    if *expected == user_pubkey()
+                        && *actual == other_keypair.x_only_public_key().0
+            ));
+
+            // Sign with the other key anyway: the program must reject it too.
+            signed_with(case.branch, env, &other_keypair)
+        });
+        assert_spend_outcome(case.name, &result, case.should_succeed);
+    }
+
+    #[test]
+    fn test_signed_witness_reports_missing_utxos() {
+        let parameters = parameters_from_hex(COLLATERAL_HEX, PREMIUM_HEX, SETTLEMENT_HEX)
+            .expect("distinct asset ids should be accepted");
+        let option_offer = OptionOffer::new(parameters);
+        let branch = OptionOfferBranch::Withdraw {
+            amount: 1_000,
+            is_change_needed: false,
+        };
+
+        let mut pst = PartiallySignedTransaction::new_v2();
+        pst.add_input(Input::from_prevout(OutPoint::default()));
+
+        assert!(matches!(
+            option_offer.get_env(&pst, 0),
+            Err(crate::error::ProgramError::NoUtxosProvided)
+        ));
+        assert!(matches!(
+            option_offer.get_signed_witness(branch, &pst, 0, &user_keypair()),
+            Err(OptionOfferError::Program(
+                crate::error::ProgramError::NoUtxosProvided
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_required_premium_and_settlement() {
+        let parameters = parameters_from_hex(COLLATERAL_HEX, PREMIUM_HEX, SETTLEMENT_HEX)
+            .expect("distinct asset ids should be accepted");
+
+        assert_eq!(parameters.required_premium(600), Some(6_000));
+        assert_eq!(parameters.required_settlement(600), Some(6_000));
+        assert_eq!(parameters.required_premium(0), Some(0));
+
+        let boundary = u64::MAX / 10;
+        assert_eq!(parameters.required_premium(boundary), Some(boundary * 10));
+        assert_eq!(parameters.required_settlement(boundary + 1), None);
+    }
+
+    #[test]
+    fn test_from_internal_key_changes_address_only() {
+        let parameters = parameters_from_hex(COLLATERAL_HEX, PREMIUM_HEX, SETTLEMENT_HEX)
+            .expect("distinct asset ids should be accepted");
+        let option_offer = OptionOffer::new(parameters);
+        let nums_key = tweaked_unspendable_key([1; 32]).expect("valid tweak");
+
+        let rekeyed = OptionOffer::from_internal_key(nums_key, parameters);
+
+        assert_ne!(
+            rekeyed.get_script_pubkey(),
+            option_offer.get_script_pubkey()
+        );
+        assert_eq!(
+            OptionOffer::new(rekeyed.parameters).get_script_pubkey(),
+            option_offer.get_script_pubkey()
+        );
+        assert_eq!(
+            OptionOffer::from_internal_key(unspendable_internal_key(), parameters)
+                .get_script_pubkey(),
+            option_offer.get_script_pubkey()
+        );
+    }
+
+    fn asset_id(bytes: [u8; 32]) -> AssetId {
+        AssetId::from_slice(&bytes).expect("32-byte asset id")
+    }
+
+    proptest! {
+        #[test]
+        fn prop_try_from_hex_round_trips_distinct_assets(
+            bytes in any::<[[u8; 32]; 3]>(),
+            collateral_per_contract: u64,
+            premium_per_collateral: u64,
+            expiry_time: u32,
+        ) {
+            let [collateral, premium, settlement] = bytes.map(asset_id);
+            let distinct = collateral != premium && collateral != settlement && premium != settlement;
+
+            let result = OptionOfferParameters::try_from_hex(
+                &collateral.to_string(),
+                &premium.to_string(),
+                &settlement.to_string(),
+                collateral_per_contract,
+                premium_per_collateral,
+                expiry_time,
+                user_pubkey(),
+                SimplicityNetwork::LiquidTestnet,
+            );
+
+            prop_assert_eq!(result.is_ok(), distinct);
+            if let Ok(parameters) = result {
+                prop_assert_eq!(parameters.collateral_asset_id, collateral);
+                prop_assert_eq!(parameters.premium_asset_id, premium);
+                prop_assert_eq!(parameters.settlement_asset_id, settlement);
+                prop_assert_eq!(parameters.collateral_per_contract, collateral_per_contract);
+                prop_assert_eq!(parameters.premium_per_collateral, premium_per_collateral);
+                prop_assert_eq!(parameters.expiry_time, expiry_time);
+            }
+        }
+
+        #[test]
+        fn prop_try_from_hex_rejects_duplicate_assets(bytes in any::<[[u8; 32]; 2]>()) {
+            let [shared, other] = bytes.map(asset_id);
+            prop_assume!(shared != other);
+
+            let result = parameters_from_hex(
+                &shared.to_string(),
+                &other.to_string(),
+                &shared.to_string(),
+            );
+
+            prop_assert!(
+                matches!(
+                    result,
+                    Err(OptionOfferError::DuplicateAssetId {
+                        first: "collateral",
+                        second: "settlement",
+                        asset_id,
+                    }) if asset_id == shared
+                ),
+                "unexpected result: {:?}",
+                result
+            );
+        }
+
+        #[test]
+        fn prop_try_from_hex_rejects_malformed_assets(value in "\\PC*") {
+            prop_assume!(AssetId::from_str(&value).is_err());
+
+            let result = parameters_from_hex(COLLATERAL_HEX, &value, SETTLEMENT_HEX);
+
+            prop_assert!(
+                matches!(
+                    result,
+                    Err(OptionOfferError::InvalidAssetId { field: "premium", .. })
+                ),
+                "unexpected result: {:?}",
+                result
+            );
+        }
+
+        #[test]
+        fn prop_required_amounts_match_wide_product(
+            collateral_amount: u64,
+            collateral_per_contract: u64,
+            premium_per_collateral: u64,
+        ) {
+            let parameters = OptionOfferParameters {
+                collateral_per_contract,
+                premium_per_collateral,
+                ..parameters_from_hex(COLLATERAL_HEX, PREMIUM_HEX, SETTLEMENT_HEX)
+                    .expect("distinct asset ids should be accepted")
+            };
+
+            let expected = |per: u64| {
+                u64::try_from(u128::from(collateral_amount) * u128::from(per)).ok()
+            };
+
+            prop_assert_eq!(
+                parameters.required_premium(collateral_amount),
+                expected(premium_per_collateral)
+            );
+            prop_assert_eq!(
+                parameters.required_settlement(collateral_amount),
+                expected(collateral_per_contract)
+            );
+        }
+    }
+
+    proptest! {
+        // Every case compiles the program, so keep the case count small.
+        #![proptest_config(ProptestConfig::with_cases(8))]
+
+        #[test]
+        fn prop_address_derivation_follows_parameters(
+            collateral_per_contract: u64,
+            premium_per_collateral: u64,
+            expiry_time: u32,
+        ) {
+            let parameters = OptionOfferParameters {
+                collateral_per_contract,
+                premium_per_collateral,
+                expiry_time,
+                ..parameters_from_hex(COLLATERAL_HEX, PREMIUM_HEX, SETTLEMENT_HEX)
+                    .expect("distinct asset ids should be accepted")
+            };
+            let script_pubkey = OptionOffer::new(parameters).get_script_pubkey();
+
+            prop_assert_eq!(&OptionOffer::new(parameters).get_script_pubkey(), &script_pubkey);
+
+            let changed = OptionOfferParameters {
+                expiry_time: expiry_time.wrapping_add(1),
+                ..parameters
+            };
+            prop_assert_ne!(OptionOffer::new(changed).get_script_pubkey(), script_pubkey);
+        }
+    }
+}
@@ -1,15 +1,31 @@
 //! Covered-call options contract with five spending branches: `Fund`,
 //! `Exercise`, `Settlement`, `Expiry`, and `Cancel`.
 
+use rand_chacha::ChaCha20Rng;
+use rand_chacha::rand_core::SeedableRng;
 use simplex::either::{Left, Right};
 use simplex::program::Program;
 use simplex::provider::SimplicityNetwork;
-use simplex::simplicityhl::elements::{AssetId, secp256k1_zkp::XOnlyPublicKey};
+use simplex::simplicityhl::elements::secp256k1_zkp::{Tweak, XOnlyPublicKey};
+use simplex::simplicityhl::elements::{AssetId, OutPoint, TxOutSecrets};
+use simplex::simplicityhl::simplicity::hashes::{Hash, HashEngine, sha256};
 
 use crate::artifacts::options::OptionsProgram;
 use crate::artifacts::options::derived_options::{OptionsArguments, OptionsWitness};
 use crate::programs::program::SimplexProgram;
 
+/// Errors returned when an options covenant does not hold what its terms allow.
+#[derive(Debug, thiserror::Error)]
+pub enum OptionsError {
+    /// Returned when a covenant balance is not a whole number of contracts.
+    #[error("Covenant {field} {amount} is not a multiple of {per_contract} per contract")]
+    NotWholeContracts {
+        field: &'static str,
+        amount: u64,
+        per_contract: u64,
+    },
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct OptionsParameters {
     pub start_time: u32,
@@ -25,6 +41,76 @@ pub struct OptionsParameters {
     pub network: SimplicityNetwork,
 }
 
+impl OptionsParameters {
+    /// Number of contracts still backed by a covenant collateral UTXO of
+    /// `collateral_amount`.
+    ///
+    /// Returns `None` if `collateral_per_contract` is zero or the amount is not
+    /// a whole number of contracts, which no branch of the covenant produces.
+    #[must_use]
+    pub const fn remaining_contracts(&self, collateral_amount: u64) -> Option<u64> {
+        if self.collateral_per_contract == 0
+            || !collateral_amount.is_multiple_of(self.collateral_per_contract)
+        {
+            return None;
+        }
+
+        Some(collateral_amount / self.collateral_per_contract)
+    }
+
+    /// Check the explicit collateral and settlement balances held by the
+    /// covenant against the contract terms.
+    ///
+    /// Every branch moves collateral and settlement in whole contracts, so
+    /// both balances must be multiples of their per-contract amounts. An
+    /// empty balance is always accepted, even with a zero per-contract amount.
+    ///
+    /// # Errors
+    /// Returns [`OptionsError::NotWholeContracts`] for the first balance that
+    /// is not a whole number of contracts.
+    pub const fn check_covenant_funding(
+        &self,
+        collateral_amount: u64,
+        settlement_amount: u64,
+    ) -> Result<OptionsCovenantFunding, OptionsError> {
+        if !collateral_amount.is_multiple_of(self.collateral_per_contract) {
+            return Err(OptionsError::NotWholeContracts {
+                field: "collateral",
+                amount: collateral_amount,
+                per_contract: self.collateral_per_contract,
+            });
+        }
+        if !settlement_amount.is_multiple_of(self.settlement_per_contract) {
+            return Err(OptionsError::NotWholeContracts {
+                field: "settlement",
+                amount: settlement_amount,
+                per_contract: self.settlement_per_contract,
+            });
+        }
+
+        let remaining_contracts = match collateral_amount.checked_div(self.collateral_per_contract)
+        {
+            Some(contracts) => contracts,
+            None => 0,
+        };
+
+        Ok(OptionsCovenantFunding {
+            collateral_amount,
+            settlement_amount,
+            remaining_contracts,
+        })
+    }
+}
+
+/// Holdings of an options covenant, as checked by
+/// [`OptionsParameters::check_covenant_funding`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OptionsCovenantFunding {
+    pub collateral_amount: u64,
+    pub settlement_amount: u64,
+    pub remaining_contracts: u64,
+}
+
 impl From<OptionsParameters> for OptionsArguments {
     fn from(value: OptionsParameters) -> Self {
         Self {
@@ -73,6 +159,53 @@ impl OptionsFundingBlinders {
             output_grantor_vbf: [0; 32],
         }
     }
+
+    /// Collect the blinding factors of the reissuance token inputs and of the
+    /// outputs re-locking them, in the layout the `Fund` branch expects.
+    #[must_use]
+    pub fn from_secrets(
+        input_option: &TxOutSecrets,
+        input_grantor: &TxOutSecrets,
+        output_option: &TxOutSecrets,
+        output_grantor: &TxOutSecrets,
+    ) -> Self {
+        Self {
+            input_option_abf: tweak_bytes(input_option.asset_bf.into_inner()),
+            input_option_vbf: tweak_bytes(input_option.value_bf.into_inner()),
+            input_grantor_abf: tweak_bytes(input_grantor.asset_bf.into_inner()),
+            input_grantor_vbf: tweak_bytes(input_grantor.value_bf.into_inner()),
+            output_option_abf: tweak_bytes(output_option.asset_bf.into_inner()),
+            output_option_vbf: tweak_bytes(output_option.value_bf.into_inner()),
+            output_grantor_abf: tweak_bytes(output_grantor.asset_bf.into_inner()),
+            output_grantor_vbf: tweak_bytes(output_grantor.value_bf.into_inner()),
+        }
+    }
+}
+
+fn tweak_bytes(tweak: Tweak) -> [u8; 32] {
+    let mut bytes = [0_u8; 32];
+    bytes.copy_from_slice(tweak.as_ref());
+    bytes
+}
+
+/// Seed a blinding RNG from `seed` and the first input of the funding
+/// transaction.
+///
+/// PSET blinding draws every output blinding factor, ephemeral key and proof
+/// nonce from the RNG it is given, so blinding with this RNG makes a funding
+/// transaction byte-for-byte reproducible in tests and golden vectors. The
+/// RNG seed is a tagged SHA256 of `seed` and `outpoint`.
+#[must_use]
+pub fn deterministic_blinding_rng(seed: [u8; 32], outpoint: OutPoint) -> ChaCha20Rng {
+    let tag = sha256::Hash::hash(b"OptionsFundingBlinders");
+    let mut eng = sha256::Hash::engine();
+    eng.input(tag.as_byte_array());
+    eng.input(tag.as_byte_array());
+    eng.input(&seed);
+    eng.input(outpoint.txid.as_byte_array());
+    eng.input(&outpoint.vout.to_le_bytes());
+
+    ChaCha20Rng::from_seed(sha256::Hash::from_engine(eng).to_byte_array())
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -114,6 +247,11 @@ impl Options {
         }
     }
 
+    /// Build the options covenant with `internal_key` as its taproot
+    /// internal key instead of the BIP-0341 unspendable key.
+    ///
+    /// A key-path spend skips the expiry and settlement rules altogether, so
+    /// `internal_key` must be unspendable, see [`crate::programs`].
     #[must_use]
     pub fn from_internal_key(internal_key: XOnlyPublicKey, parameters: OptionsParameters) -> Self {
         Self {
@@ -197,3 +335,626 @@ impl SimplexProgram for Options {
         &self.parameters.network
     }
 }
+
+#[cfg(test)]
+mod options_tests {
+    use super::*;
+
+    use std::collections::HashMap;
+
+    use anyhow::Result;
+    use proptest::prelude::*;
+
+    use crate::programs::simulation::Destination::{Burn, Covenant, Receiver};
+    use crate::programs::simulation::{
+        CompiledCovenant, Destination, SimulatedSpend, assert_spend_outcome,
+    };
+    use crate::scripts::{tweaked_unspendable_key, unspendable_internal_key};
+
+    use simplex::program::{ArgumentsTrait, WitnessTrait};
+    use simplex::simplicityhl::elements::bitcoin::PublicKey;
+    use simplex::simplicityhl::elements::confidential::{AssetBlindingFactor, ValueBlindingFactor};
+    use simplex::simplicityhl::elements::pset::{Input, Output, PartiallySignedTransaction};
+    use simplex::simplicityhl::elements::secp256k1_zkp as secp256k1;
+    use simplex::simplicityhl::elements::{Address, AddressParams, LockTime, TxOut, Txid};
+
+    const COLLATERAL_PER_CONTRACT: u64 = 1_000;
+
+    fn parameters() -> OptionsParameters {
+        let asset = |byte: u8| AssetId::from_slice(&[byte; 32]).expect("valid asset id");
+
+        OptionsParameters {
+            start_time: 1_700_000_000,
+            expiry_time: 1_700_086_400,
+            collateral_per_contract: COLLATERAL_PER_CONTRACT,
+            settlement_per_contract: 50,
+            collateral_asset_id: asset(1),
+            settlement_asset_id: asset(2),
+            option_token_asset: asset(3),
+            option_reissuance_token_asset: asset(4),
+            grantor_token_asset: asset(5),
+            grantor_reissuance_token_asset: asset(6),
+            network: SimplicityNetwork::LiquidTestnet,
+        }
+    }
+
+    /// Re-lock two reissuance tokens in blinded outputs the way the funding
+    /// builder does, with an RNG from [`deterministic_blinding_rng`].
+    fn blinded_outputs(seed: [u8; 32], outpoint: OutPoint) -> Result<Vec<TxOut>> {
+        let parameters = parameters();
+        let blinding_key = PublicKey::new(secp256k1::PublicKey::from_secret_key(
+            secp256k1::SECP256K1,
+            &secp256k1::SecretKey::from_slice(&[9; 32])?,
+        ));
+
+        let script_pubkey =
+            Address::p2wpkh(&blinding_key, None, &AddressParams::LIQUID_TESTNET).script_pubkey();
+
+        let token_assets = [
+            parameters.option_reissuance_token_asset,
+            parameters.grantor_reissuance_token_asset,
+        ];
+
+        let mut pst = PartiallySignedTransaction::new_v2();
+        let mut input_secrets = HashMap::new();
+        for (index, asset_id) in token_assets.into_iter().enumerate() {
+            let mut input = Input::from_prevout(OutPoint::new(
+                outpoint.txid,
+                outpoint.vout + u32::try_from(index)?,
+            ));
+            input.witness_utxo = Some(TxOut::new_fee(1, asset_id));
+            pst.add_input(input);
+            input_secrets.insert(
+                index,
+                TxOutSecrets::new(
+                    asset_id,
+                    AssetBlindingFactor::zero(),
+                    1,
+                    ValueBlindingFactor::zero(),
+                ),
+            );
+
+            let mut output =
+                Output::new_explicit(script_pubkey.clone(), 1, asset_id, Some(blinding_key));
+            output.blinder_index = Some(0);
+            pst.add_output(output);
+        }
+
+        pst.blind_last(
+            &mut deterministic_blinding_rng(seed, outpoint),
+            secp256k1::SECP256K1,
+            &input_secrets,
+        )?;
+
+        Ok(pst.extract_tx()?.output)
+    }
+
+    #[test]
+    fn test_deterministic_blinding_rng_reproduces_blinded_outputs() -> Result<()> {
+        let outpoint = OutPoint::new(Txid::from_byte_array([7; 32]), 1);
+
+        let outputs = blinded_outputs([1; 32], outpoint)?;
+
+        assert!(outputs[0].asset.is_confidential());
+        assert!(outputs[1].value.is_confidential());
+        assert_eq!(outputs, blinded_outputs([1; 32], outpoint)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deterministic_blinding_rng_depends_on_seed_and_outpoint() -> Result<()> {
+        let outpoint = OutPoint::new(Txid::from_byte_array([7; 32]), 1);
+        let outputs = blinded_outputs([1; 32], outpoint)?;
+
+        assert_ne!(outputs, blinded_outputs([2; 32], outpoint)?);
+        assert_ne!(
+            outputs,
+            blinded_outputs([1; 32], OutPoint::new(outpoint.txid, 2))?
+        );
+
+        Ok(())
+    }
+
+    /// A hand-built spend of the options covenant and whether the branch
+    /// should accept it.
+    struct BranchCase {
+        name: &'static str,
+        branch: OptionsBranch,
+        spend: SimulatedSpend,
+        should_succeed: bool,
+    }
+
+    fn spend(
+        lock_time: u32,
+        covenant_input: (AssetId, u64),
+        other_inputs: Vec<(AssetId, u64)>,
+        outputs: Vec<(Destination, AssetId, u64)>,
+    ) -> SimulatedSpend {
+        SimulatedSpend {
+            lock_time: LockTime::from_time(lock_time).expect("valid time lock"),
+            covenant_inputs: vec![covenant_input],
+            other_inputs,
+            outputs,
+        }
+    }
+
+    /// Cover the exercise, settlement, expiry, and cancel branches on
+    /// hand-built spends of ten contracts.
+    ///
+    /// `Fund` is left to the regtest suite: it needs blinded reissuance token
+    /// inputs and issuances, which a hand-built explicit spend cannot provide.
+    #[allow(clippy::too_many_lines)]
+    fn branch_cases(parameters: &OptionsParameters) -> Vec<BranchCase> {
+        let collateral = parameters.collateral_asset_id;
+        let settlement = parameters.settlement_asset_id;
+        let option_token = parameters.option_token_asset;
+        let grantor_token = parameters.grantor_token_asset;
+        let start = parameters.start_time;
+        let expiry = parameters.expiry_time;
+
+        vec![
+            BranchCase {
+                name: "exercise full",
+                branch: OptionsBranch::Exercise {
+                    is_change_needed: false,
+                    amount_to_burn: 10,
+                    collateral_amount: 10_000,
+                    settlement_amount: 500,
+                },
+                spend: spend(
+                    start,
+                    (collateral, 10_000),
+                    vec![(option_token, 10), (settlement, 500)],
+                    vec![
+                        (Burn, option_token, 10),
+                        (Covenant, settlement, 500),
+                        (Receiver, collateral, 10_000),
+                    ],
+                ),
+                should_succeed: true,
+            },
+            BranchCase {
+                name: "exercise with change",
+                branch: OptionsBranch::Exercise {
+                    is_change_needed: true,
+                    amount_to_burn: 4,
+                    collateral_amount: 4_000,
+                    settlement_amount: 200,
+                },
+                spend: spend(
+                    start,
+                    (collateral, 10_000),
+                    vec![(option_token, 4), (settlement, 200)],
+                    vec![
+                        (Covenant, collateral, 6_000),
+                        (Burn, option_token, 4),
+                        (Covenant, settlement, 200),
+                        (Receiver, collateral, 4_000),
+                    ],
+                ),
+                should_succeed: true,
+            },
+            BranchCase {
+                name: "exercise before start time",
+                branch: OptionsBranch::Exercise {
+                    is_change_needed: false,
+                    amount_to_burn: 10,
+                    collateral_amount: 10_000,
+                    settlement_amount: 500,
+                },
+                spend: spend(
+                    start - 1,
+                    (collateral, 10_000),
+                    vec![(option_token, 10), (settlement, 500)],
+                    vec![
+                        (Burn, option_token, 10),
+                        (Covenant, settlement, 500),
+                        (Receiver, collateral, 10_000),
+                    ],
+                ),
+                should_succeed: false,
+            },
+            BranchCase {
+                name: "exercise paying settlement to the receiver",
+                branch: OptionsBranch::Exercise {
+                    is_change_needed: true,
+                    amount_to_burn: 4,
+                    collateral_amount: 4_000,
+                    settlement_amount: 200,
+                },
+                spend: spend(
+                    start,
+                    (collateral, 10_000),
+                    vec![(option_token, 4), (settlement, 200)],
+                    vec![
+                        (Covenant, collateral, 6_000),
+                        (Burn, option_token, 4),
+                        (Receiver, settlement, 200),
+                        (Receiver, collateral, 4_000),
+                    ],
+                ),
+                should_succeed: false,
+            },
+            BranchCase {
+                name: "exercise underpaying settlement",
+                branch: OptionsBranch::Exercise {
+                    is_change_needed: false,
+                    amount_to_burn: 10,
+                    collateral_amount: 10_000,
+                    settlement_amount: 499,
+                },
+                spend: spend(
+                    start,
+                    (collateral, 10_000),
+                    vec![(option_token, 10), (settlement, 499)],
+                    vec![
+                        (Burn, option_token, 10),
+                        (Covenant, settlement, 499),
+                        (Receiver, collateral, 10_000),
+                    ],
+                ),
+                should_succeed: false,
+            },
+            BranchCase {
+                name: "settlement full",
+                branch: OptionsBranch::Settlement {
+                    is_change_needed: false,
+                    amount_to_burn: 10,
+                    settlement_amount: 500,
+                },
+                spend: spend(
+                    start,
+                    (settlement, 500),
+                    vec![(grantor_token, 10)],
+                    vec![(Burn, grantor_token, 10), (Receiver, settlement, 500)],
+                ),
+                should_succeed: true,
+            },
+            BranchCase {
+                name: "settlement with change",
+                branch: OptionsBranch::Settlement {
+                    is_change_needed: true,
+                    amount_to_burn: 4,
+                    settlement_amount: 200,
+                },
+                spend: spend(
+                    start,
+                    (settlement, 500),
+                    vec![(grantor_token, 4)],
+                    vec![
+                        (Covenant, settlement, 300),
+                        (Burn, grantor_token, 4),
+                        (Receiver, settlement, 200),
+                    ],
+                ),
+                should_succeed: true,
+            },
+            BranchCase {
+                name: "settlement overpaying the grantor",
+                branch: OptionsBranch::Settlement {
+                    is_change_needed: true,
+                    amount_to_burn: 4,
+                    settlement_amount: 250,
+                },
+                spend: spend(
+                    start,
+                    (settlement, 500),
+                    vec![(grantor_token, 4)],
+                    vec![
+                        (Covenant, settlement, 250),
+                        (Burn, grantor_token, 4),
+                        (Receiver, settlement, 250),
+                    ],
+                ),
+                should_succeed: false,
+            },
+            BranchCase {
+                name: "expiry full",
+                branch: OptionsBranch::Expiry {
+                    is_change_needed: false,
+                    amount_to_burn: 10,
+                    collateral_amount: 10_000,
+                },
+                spend: spend(
+                    expiry,
+                    (collateral, 10_000),
+                    vec![(grantor_token, 10)],
+                    vec![(Burn, grantor_token, 10), (Receiver, collateral, 10_000)],
+                ),
+                should_succeed: true,
+            },
+            BranchCase {
+                name: "expiry with change",
+                branch: OptionsBranch::Expiry {
+                    is_change_needed: true,
+                    amount_to_burn: 4,
+                    collateral_amount: 4_000,
+                },
+                spend: spend(
+                    expiry,
+                    (collateral, 10_000),
+                    vec![(grantor_token, 4)],
+                    vec![
+                        (Covenant, collateral, 6_000),
+                        (Burn, grantor_token, 4),
+                        (Receiver, collateral, 4_000),
+                    ],
+                ),
+                should_succeed: true,
+            },
+            BranchCase {
+                name: "expiry before expiry time",
+                branch: OptionsBranch::Expiry {
+                    is_change_needed: false,
+                    amount_to_burn: 10,
+                    collateral_amount: 10_000,
+                },
+                spend: spend(
+                    expiry - 1,
+                    (collateral, 10_000),
+                    vec![(grantor_token, 10)],
+                    vec![(Burn, grantor_token, 10), (Receiver, collateral, 10_000)],
+                ),
+                should_succeed: false,
+            },
+            BranchCase {
+                name: "cancel full",
+                branch: OptionsBranch::Cancel {
+                    is_change_needed: false,
+                    amount_to_burn: 10,
+                    collateral_amount: 10_000,
+                },
+                spend: spend(
+                    start,
+                    (collateral, 10_000),
+                    vec![(option_token, 10), (grantor_token, 10)],
+                    vec![
+                        (Burn, option_token, 10),
+                        (Burn, grantor_token, 10),
+                        (Receiver, collateral, 10_000),
+                    ],
+                ),
+                should_succeed: true,
+            },
+            BranchCase {
+                name: "cancel with change",
+                branch: OptionsBranch::Cancel {
+                    is_change_needed: true,
+                    amount_to_burn: 4,
+                    collateral_amount: 4_000,
+                },
+                spend: spend(
+                    start,
+                    (collateral, 10_000),
+                    vec![(option_token, 4), (grantor_token, 4)],
+                    vec![
+                        (Covenant, collateral, 6_000),
+                        (Burn, option_token, 4),
+                        (Burn, grantor_token, 4),
+                        (Receiver, collateral, 4_000),
+                    ],
+                ),
+                should_succeed: true,
+            },
+            BranchCase {
+                name: "cancel keeping the option tokens",
+                branch: OptionsBranch::Cancel {
+                    is_change_needed: false,
+                    amount_to_burn: 10,
+                    collateral_amount: 10_000,
+                },
+                spend: spend(
+                    start,
+                    (collateral, 10_000),
+                    vec![(option_token, 10), (grantor_token, 10)],
+                    vec![
+                        (Receiver, option_token, 10),
+                        (Burn, grantor_token, 10),
+                        (Receiver, collateral, 10_000),
+                    ],
+                ),
+                should_succeed: false,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_simulate_branches() {
+        let parameters = parameters();
+        let covenant = CompiledCovenant::new(
+            &Options::new(parameters),
+            OptionsProgram::SOURCE,
+            OptionsArguments::from(parameters).build_arguments(),
+        );
+
+        for case in branch_cases(&parameters) {
+            let witness = Options::get_witness(case.branch).build_witness();
+            let result = covenant.execute_spend(&case.spend, |_, _, _| witness.clone());
+            assert_spend_outcome(case.name, &result, case.should_succeed);
+        }
+    }
+
+    #[test]
+    fn test_from_internal_key_changes_address_only() {
+        let options = Options::new(parameters());
+        let nums_key = tweaked_unspendable_key([1; 32]).expect("valid tweak");
+
+        let rekeyed = Options::from_internal_key(nums_key, parameters());
+
+        assert_ne!(rekeyed.get_script_pubkey(), options.get_script_pubkey());
+        assert_eq!(
+            Options::new(rekeyed.parameters).get_script_pubkey(),
+            options.get_script_pubkey()
+        );
+        assert_eq!(
+            Options::from_internal_key(unspendable_internal_key(), parameters())
+                .get_script_pubkey(),
+            options.get_script_pubkey()
+        );
+    }
+
+    #[test]
+    fn test_remaining_contracts_for_full_and_partial_positions() {
+        let parameters = parameters();
+
+        assert_eq!(
+            parameters.remaining_contracts(10 * COLLATERAL_PER_CONTRACT),
+            Some(10)
+        );
+        assert_eq!(
+            parameters.remaining_contracts(7 * COLLATERAL_PER_CONTRACT),
+            Some(7)
+        );
+        assert_eq!(parameters.remaining_contracts(0), Some(0));
+    }
+
+    #[test]
+    fn test_check_covenant_funding() {
+        let mut parameters = parameters();
+
+        assert_eq!(
+            parameters
+                .check_covenant_funding(7 * COLLATERAL_PER_CONTRACT, 150)
+                .ok(),
+            Some(OptionsCovenantFunding {
+                collateral_amount: 7 * COLLATERAL_PER_CONTRACT,
+                settlement_amount: 150,
+                remaining_contracts: 7,
+            })
+        );
+        assert!(matches!(
+            parameters.check_covenant_funding(7 * COLLATERAL_PER_CONTRACT + 1, 0),
+            Err(OptionsError::NotWholeContracts {
+                field: "collateral",
+                ..
+            })
+        ));
+        assert!(matches!(
+            parameters.check_covenant_funding(0, 149),
+            Err(OptionsError::NotWholeContracts {
+                field: "settlement",
+                ..
+            })
+        ));
+
+        parameters.settlement_per_contract = 0;
+        assert!(
+            parameters
+                .check_covenant_funding(COLLATERAL_PER_CONTRACT, 0)
+                .is_ok()
+        );
+        assert!(parameters.check_covenant_funding(0, 1).is_err());
+    }
+
+    #[test]
+    fn test_remaining_contracts_rejects_invalid_amounts() {
+        let mut parameters = parameters();
+
+        assert_eq!(
+            parameters.remaining_contracts(7 * COLLATERAL_PER_CONTRACT + 1),
+            None
+        );
+
+        parameters.collateral_per_contract = 0;
+        assert_eq!(
+            parameters.remaining_contracts(COLLATERAL_PER_CONTRACT),
+            None
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn prop_remaining_contracts_counts_whole_contracts(
+            per_contract in 1..=u64::from(u32::MAX),
+            contracts in 0..=u64::from(u32::MAX),
+            remainder: u64,
+        ) {
+            let parameters = OptionsParameters {
+                collateral_per_contract: per_contract,
+                ..parameters()
+            };
+            let remainder = remainder % per_contract;
+            let collateral_amount = per_contract * contracts + remainder;
+
+            prop_assert_eq!(
+                parameters.remaining_contracts(collateral_amount),
+                (remainder == 0).then_some(contracts)
+            );
+        }
+
+        #[test]
+        fn prop_remaining_contracts_rejects_zero_per_contract(collateral_amount: u64) {
+            let parameters = OptionsParameters {
+                collateral_per_contract: 0,
+                ..parameters()
+            };
+
+            prop_assert_eq!(parameters.remaining_contracts(collateral_amount), None);
+        }
+
+        #[test]
+        fn prop_check_covenant_funding_agrees_with_remaining_contracts(
+            collateral_per_contract: u64,
+            settlement_per_contract: u64,
+            collateral_amount: u64,
+            settlement_amount: u64,
+        ) {
+            let parameters = OptionsParameters {
+                collateral_per_contract,
+                settlement_per_contract,
+                ..parameters()
+            };
+            let collateral_whole = collateral_amount.is_multiple_of(collateral_per_contract);
+            let settlement_whole = settlement_amount.is_multiple_of(settlement_per_contract);
+
+            match parameters.check_covenant_funding(collateral_amount, settlement_amount) {
+                Ok(funding) => {
+                    prop_assert!(collateral_whole && settlement_whole);
+                    prop_assert_eq!(funding.collateral_amount, collateral_amount);
+                    prop_assert_eq!(funding.settlement_amount, settlement_amount);
+                    prop_assert_eq!(
+                        funding.remaining_contracts,
+                        parameters.remaining_contracts(collateral_amount).unwrap_or(0)
+                    );
+                }
+                Err(OptionsError::NotWholeContracts { field, amount, .. }) => {
+                    if collateral_whole {
+                        prop_assert!(!settlement_whole);
+                        prop_assert_eq!((field, amount), ("settlement", settlement_amount));
+                    } else {
+                        prop_assert_eq!((field, amount), ("collateral", collateral_amount));
+                    }
+                }
+            }
+        }
+    }
+
+    proptest! {
+        // Every case compiles the program, so keep the case count small.
+        #![proptest_config(ProptestConfig::with_cases(4))]
+
+        #[test]
+        fn prop_address_derivation_follows_parameters(
+            collateral_per_contract: u64,
+            settlement_per_contract: u64,
+            expiry_time: u32,
+        ) {
+            let parameters = OptionsParameters {
+                expiry_time,
+                collateral_per_contract,
+                settlement_per_contract,
+                ..parameters()
+            };
+            let script_pubkey = Options::new(parameters).get_script_pubkey();
+
+            prop_assert_eq!(&Options::new(parameters).get_script_pubkey(), &script_pubkey);
+
+            let changed = OptionsParameters {
+                expiry_time: expiry_time.wrapping_add(1),
+                ..parameters
+            };
+            prop_assert_ne!(Options::new(changed).get_script_pubkey(), script_pubkey);
+        }
+    }
+}
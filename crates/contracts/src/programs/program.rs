@@ -1,6 +1,13 @@
+use std::sync::Arc;
+
 use simplex::program::Program;
 use simplex::provider::SimplicityNetwork;
-use simplex::simplicityhl::elements::Script;
+use simplex::simplicityhl::elements::pset::PartiallySignedTransaction;
+use simplex::simplicityhl::elements::{Script, Transaction};
+use simplex::simplicityhl::simplicity::jet::elements::ElementsEnv;
+
+use crate::error::ProgramError;
+use crate::runner::get_env;
 
 pub trait SimplexProgram {
     fn get_script_pubkey(&self) -> Script {
@@ -11,6 +18,19 @@ pub trait SimplexProgram {
         self.get_program().get_script_hash(self.get_network())
     }
 
+    /// Build the environment for spending input `input_index` of `pst` with
+    /// this contract, see [`get_env`].
+    ///
+    /// # Errors
+    /// Returns an error if the environment cannot be built.
+    fn get_env(
+        &self,
+        pst: &PartiallySignedTransaction,
+        input_index: usize,
+    ) -> Result<ElementsEnv<Arc<Transaction>>, ProgramError> {
+        get_env(self.get_program(), pst, input_index, self.get_network())
+    }
+
     fn get_program(&self) -> &Program;
 
     fn get_network(&self) -> &SimplicityNetwork;
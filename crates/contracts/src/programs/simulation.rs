@@ -0,0 +1,225 @@
+//! Node-free harness for running covenant branches in unit tests.
+//!
+//! Spends are assembled as PSETs and executed with [`run_program`] against a
+//! covenant compiled once per contract.
+
+use std::sync::Arc;
+
+use simplex::global::GlobalConfig;
+use simplex::provider::SimplicityNetwork;
+use simplex::simplicityhl::ast::ElementsJetHinter;
+use simplex::simplicityhl::elements::confidential::{Asset, Value};
+use simplex::simplicityhl::elements::hashes::Hash;
+use simplex::simplicityhl::elements::pset::PartiallySignedTransaction;
+use simplex::simplicityhl::elements::taproot::ControlBlock;
+use simplex::simplicityhl::elements::{
+    AssetId, LockTime, OutPoint, Script, Sequence, Transaction, TxOut, Txid, WPubkeyHash,
+};
+use simplex::simplicityhl::simplicity::jet::elements::{ElementsEnv, ElementsUtxo};
+use simplex::simplicityhl::tracker::TrackerLogLevel;
+use simplex::simplicityhl::{Arguments, CompiledProgram, UnstableFeatures, WitnessValues};
+use simplex::transaction::{PartialInput, PartialOutput, UTXO};
+
+use crate::error::ProgramError;
+use crate::programs::program::SimplexProgram;
+use crate::runner::run_program;
+use crate::scripts::{control_block, create_p2tr_address, unspendable_internal_key};
+
+/// Where an output of a [`SimulatedSpend`] pays to.
+#[derive(Debug, Clone, Copy)]
+pub enum Destination {
+    /// Back to the covenant being spent.
+    Covenant,
+    /// To an unrelated P2WPKH receiver.
+    Receiver,
+    /// To an `OP_RETURN` burn output.
+    Burn,
+}
+
+/// A hand-built spend of a covenant.
+///
+/// The covenant inputs come first, followed by inputs from other scripts;
+/// all amounts and assets are explicit.
+pub struct SimulatedSpend {
+    pub lock_time: LockTime,
+    pub covenant_inputs: Vec<(AssetId, u64)>,
+    pub other_inputs: Vec<(AssetId, u64)>,
+    pub outputs: Vec<(Destination, AssetId, u64)>,
+}
+
+impl SimulatedSpend {
+    pub fn to_pst(&self, covenant_script: &Script) -> PartiallySignedTransaction {
+        let mut pst = PartiallySignedTransaction::new_v2();
+
+        let inputs = self
+            .covenant_inputs
+            .iter()
+            .map(|&(asset_id, amount)| (covenant_script.clone(), asset_id, amount))
+            .chain(
+                self.other_inputs
+                    .iter()
+                    .map(|&(asset_id, amount)| (receiver_script(), asset_id, amount)),
+            );
+        for (vout, (script_pubkey, asset_id, amount)) in (0_u32..).zip(inputs) {
+            let utxo = UTXO {
+                outpoint: OutPoint::new(Txid::from_byte_array([1; 32]), vout),
+                txout: explicit_txout(script_pubkey, asset_id, amount),
+                secrets: None,
+            };
+            pst.add_input(
+                PartialInput::new(utxo)
+                    .with_sequence(Sequence::ENABLE_LOCKTIME_NO_RBF)
+                    .with_locktime(self.lock_time)
+                    .to_input(),
+            );
+        }
+
+        for &(destination, asset_id, amount) in &self.outputs {
+            let script_pubkey = match destination {
+                Destination::Covenant => covenant_script.clone(),
+                Destination::Receiver => receiver_script(),
+                Destination::Burn => Script::new_op_return(b"burn"),
+            };
+            pst.add_output(PartialOutput::new(script_pubkey, amount, asset_id).to_output());
+        }
+
+        pst
+    }
+}
+
+/// A covenant program compiled once for a whole set of simulated spends.
+///
+/// The Simplex [`Program`](simplex::program::Program) recompiles its source for every address, environment,
+/// and execution, so running many cases through it is slow. This keeps the
+/// compiled program and its taproot data and builds each environment directly.
+pub struct CompiledCovenant {
+    program: CompiledProgram,
+    control_block: ControlBlock,
+    script_pubkey: Script,
+    network: SimplicityNetwork,
+}
+
+impl CompiledCovenant {
+    /// Compile `source` with `arguments` under the unspendable internal key,
+    /// with the same settings Simplex compiles contract programs with.
+    ///
+    /// # Panics
+    /// Panics if the source does not compile or if the derived address differs
+    /// from the one `contract` spends from.
+    pub fn new(contract: &impl SimplexProgram, source: &str, arguments: Arguments) -> Self {
+        let program = CompiledProgram::new_with_unstable(
+            source,
+            &UnstableFeatures::all(),
+            arguments,
+            GlobalConfig::get_include_debug_symbols(),
+            Box::new(ElementsJetHinter),
+        )
+        .expect("covenant source should compile");
+
+        let cmr = program.commit().cmr();
+        let network = *contract.get_network();
+        let script_pubkey =
+            create_p2tr_address(cmr, &unspendable_internal_key(), network.address_params())
+                .script_pubkey();
+        assert_eq!(
+            script_pubkey,
+            contract.get_script_pubkey(),
+            "compiled covenant should match the contract address"
+        );
+
+        Self {
+            control_block: control_block(cmr, unspendable_internal_key()),
+            program,
+            script_pubkey,
+            network,
+        }
+    }
+
+    /// Execute the covenant on every covenant input of `spend`, building each
+    /// input's witness from the spending PSET, the input index, and its
+    /// environment.
+    pub fn execute_spend(
+        &self,
+        spend: &SimulatedSpend,
+        witness: impl Fn(
+            &PartiallySignedTransaction,
+            usize,
+            &ElementsEnv<Arc<Transaction>>,
+        ) -> WitnessValues,
+    ) -> Result<(), ProgramError> {
+        let pst = spend.to_pst(&self.script_pubkey);
+
+        for input_index in 0..spend.covenant_inputs.len() {
+            let env = self.env(&pst, input_index)?;
+            run_program(
+                &self.program,
+                witness(&pst, input_index, &env),
+                &env,
+                TrackerLogLevel::None,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn env(
+        &self,
+        pst: &PartiallySignedTransaction,
+        input_index: usize,
+    ) -> Result<ElementsEnv<Arc<Transaction>>, ProgramError> {
+        let tx = pst
+            .extract_tx()
+            .expect("simulated spends are complete transactions");
+        let utxos = pst
+            .inputs()
+            .iter()
+            .filter_map(|input| input.witness_utxo.as_ref())
+            .map(|utxo| ElementsUtxo {
+                script_pubkey: utxo.script_pubkey.clone(),
+                asset: utxo.asset,
+                value: utxo.value,
+            })
+            .collect();
+
+        Ok(ElementsEnv::new(
+            Arc::new(tx),
+            utxos,
+            u32::try_from(input_index)?,
+            self.program.commit().cmr(),
+            self.control_block.clone(),
+            None,
+            self.network.genesis_block_hash(),
+        ))
+    }
+}
+
+/// Check the outcome of [`CompiledCovenant::execute_spend`] for the case `name`.
+///
+/// A rejection only counts if the program itself failed while running, not
+/// if it could not be compiled, satisfied, or given an environment.
+pub fn assert_spend_outcome(name: &str, result: &Result<(), ProgramError>, should_succeed: bool) {
+    if should_succeed {
+        assert!(result.is_ok(), "case '{name}' was rejected: {result:?}");
+    } else {
+        assert!(
+            matches!(
+                result,
+                Err(ProgramError::Pruning(_) | ProgramError::Execution(_))
+            ),
+            "case '{name}' was not rejected by the program: {result:?}"
+        );
+    }
+}
+
+fn receiver_script() -> Script {
+    Script::new_v0_wpkh(&WPubkeyHash::from_byte_array([2; 20]))
+}
+
+fn explicit_txout(script_pubkey: Script, asset_id: AssetId, amount: u64) -> TxOut {
+    TxOut {
+        asset: Asset::Explicit(asset_id),
+        value: Value::Explicit(amount),
+        script_pubkey,
+        ..TxOut::default()
+    }
+}
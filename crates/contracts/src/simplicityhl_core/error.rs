@@ -22,6 +22,13 @@ pub enum ProgramError {
     #[error("Failed to execute program on the Bit Machine: {0}")]
     Execution(simplex::simplicityhl::simplicity::bit_machine::ExecutionError),
 
+    /// Returned when no prevouts are supplied to build an environment from.
+    #[error("No UTXOs provided to build the transaction environment")]
+    NoUtxosProvided,
+
+    #[error("UTXO {utxo_index} has an empty script pubkey")]
+    EmptyUtxoScript { utxo_index: usize },
+
     #[error("UTXO index {input_index} out of bounds (have {utxo_count} UTXOs)")]
     UtxoIndexOutOfBounds {
         input_index: usize,
@@ -35,6 +42,38 @@ pub enum ProgramError {
         actual_hash: String,
     },
 
+    #[error("Output index {output_index} out of bounds (have {output_count} outputs)")]
+    OutputIndexOutOfBounds {
+        output_index: usize,
+        output_count: usize,
+    },
+
+    /// Returned when an output that must return funds to the covenant pays elsewhere.
+    #[error(
+        "Output {output_index} must return to the covenant: expected hash {expected_hash}, got {actual_hash}"
+    )]
+    CovenantOutputMisdirected {
+        output_index: usize,
+        expected_hash: String,
+        actual_hash: String,
+    },
+
+    /// Returned when Simplex fails to build the environment for, or run, a
+    /// contract [`simplex::program::Program`].
+    #[error(transparent)]
+    Simplex(#[from] simplex::program::ProgramError),
+
     #[error("Input index exceeds u32 maximum: {0}")]
     InputIndexOverflow(#[from] std::num::TryFromIntError),
 }
+
+/// Errors returned by [`parse_liquid_address`](super::scripts::parse_liquid_address).
+#[derive(Debug, thiserror::Error)]
+pub enum AddressError {
+    #[error("Failed to parse address: {0}")]
+    Parsing(#[from] simplex::simplicityhl::elements::AddressError),
+
+    /// Returned when an address belongs to a different network than expected.
+    #[error("Address {address} does not belong to the expected network")]
+    NetworkMismatch { address: String },
+}
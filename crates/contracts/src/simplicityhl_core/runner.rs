@@ -1,8 +1,14 @@
 use std::sync::Arc;
 
+use simplex::program::{Program, ProgramTrait};
+use simplex::provider::SimplicityNetwork;
 use simplex::simplicityhl::ast::ElementsJetHinter;
+use simplex::simplicityhl::elements::Script;
+use simplex::simplicityhl::elements::pset::PartiallySignedTransaction;
 use simplex::simplicityhl::simplicity::RedeemNode;
+use simplex::simplicityhl::simplicity::bitcoin::secp256k1;
 use simplex::simplicityhl::simplicity::elements::Transaction;
+use simplex::simplicityhl::simplicity::hashes::Hash as _;
 use simplex::simplicityhl::simplicity::jet::elements::ElementsEnv;
 use simplex::simplicityhl::simplicity::{BitMachine, Value};
 use simplex::simplicityhl::tracker::{DefaultTracker, TrackerLogLevel};
@@ -10,6 +16,95 @@ use simplex::simplicityhl::{CompiledProgram, WitnessValues};
 
 use super::error::ProgramError;
 
+/// Build the environment for spending input `input_index` of `pst` with
+/// `program`.
+///
+/// Simplex only counts the inputs that carry a `witness_utxo`, so a PSET
+/// without prevouts would be reported as an out-of-bounds index. The prevouts
+/// are checked here first so the actual problem is named.
+///
+/// # Errors
+/// Returns an error if no input carries a prevout, a prevout has an empty
+/// script, or Simplex cannot build the environment for the input.
+pub fn get_env(
+    program: &Program,
+    pst: &PartiallySignedTransaction,
+    input_index: usize,
+    network: &SimplicityNetwork,
+) -> Result<ElementsEnv<Arc<Transaction>>, ProgramError> {
+    let mut utxos = pst
+        .inputs()
+        .iter()
+        .filter_map(|input| input.witness_utxo.as_ref())
+        .peekable();
+
+    if utxos.peek().is_none() {
+        return Err(ProgramError::NoUtxosProvided);
+    }
+    if let Some(utxo_index) = utxos.position(|utxo| utxo.script_pubkey.is_empty()) {
+        return Err(ProgramError::EmptyUtxoScript { utxo_index });
+    }
+
+    Ok(program.get_env(pst, input_index, network)?)
+}
+
+/// Check that every output in `expected_self_outputs` pays back to
+/// `covenant_script`.
+///
+/// Covenant branches only notice a misdirected change or re-deposit output
+/// when the program runs; this catches it while the transaction is built.
+///
+/// # Errors
+/// Returns an error for the first listed output that is missing or targets
+/// another script.
+pub fn verify_covenant_self_outputs(
+    tx: &Transaction,
+    covenant_script: &Script,
+    expected_self_outputs: &[usize],
+) -> Result<(), ProgramError> {
+    for &output_index in expected_self_outputs {
+        let output = tx
+            .output
+            .get(output_index)
+            .ok_or(ProgramError::OutputIndexOutOfBounds {
+                output_index,
+                output_count: tx.output.len(),
+            })?;
+
+        if output.script_pubkey != *covenant_script {
+            return Err(ProgramError::CovenantOutputMisdirected {
+                output_index,
+                expected_hash: covenant_script.script_hash().to_string(),
+                actual_hash: output.script_pubkey.script_hash().to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Compute the `SIGHASH_ALL` digest that `jet::sig_all_hash` produces when
+/// `program` spends input `input_index` of `pst`, ready to be handed to a
+/// signer.
+///
+/// Simplicity only exposes the `SIGHASH_ALL` message to programs, so this is
+/// the digest any covenant signature check in this crate verifies against.
+///
+/// # Errors
+/// Returns an error if the environment cannot be built, see [`get_env`].
+pub fn compute_sighash_all(
+    program: &Program,
+    pst: &PartiallySignedTransaction,
+    input_index: usize,
+    network: &SimplicityNetwork,
+) -> Result<secp256k1::Message, ProgramError> {
+    let env = get_env(program, pst, input_index, network)?;
+
+    Ok(secp256k1::Message::from_digest(
+        *env.c_tx_env().sighash_all().as_byte_array(),
+    ))
+}
+
 /// Satisfy and execute a compiled program in the provided environment.
 /// Returns the pruned program and the resulting value.
 ///
@@ -38,3 +133,183 @@ pub fn run_program(
 
     Ok((pruned, result))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use anyhow::Result;
+
+    use std::collections::HashMap;
+
+    use simplex::program::ArgumentsTrait;
+    use simplex::simplicityhl::elements::pset::{Input, Output};
+    use simplex::simplicityhl::elements::{AssetId, OutPoint, TxOut, Txid};
+    use simplex::simplicityhl::num::U256;
+    use simplex::simplicityhl::simplicity::bitcoin::XOnlyPublicKey;
+    use simplex::simplicityhl::str::WitnessName;
+    use simplex::simplicityhl::value::{UIntValue, ValueConstructible};
+    use simplex::simplicityhl::{Arguments, Value as HlValue};
+
+    use crate::scripts::unspendable_internal_key;
+
+    /// A program that only checks a BIP-340 signature by `USER_PUBKEY` over
+    /// `jet::sig_all_hash`, the way the covenant user branches do.
+    const SIGNATURE_CHECK_SOURCE: &str = "fn main() {
+        let msg: u256 = jet::sig_all_hash();
+        jet::bip_0340_verify((param::USER_PUBKEY, msg), witness::USER_SIGNATURE);
+    }";
+
+    #[derive(Clone)]
+    struct SignatureCheckArguments(XOnlyPublicKey);
+
+    impl ArgumentsTrait for SignatureCheckArguments {
+        fn build_arguments(&self) -> Arguments {
+            Arguments::from(HashMap::from([(
+                WitnessName::from_str_unchecked("USER_PUBKEY"),
+                HlValue::from(UIntValue::U256(U256::from_byte_array(self.0.serialize()))),
+            )]))
+        }
+    }
+
+    fn network() -> SimplicityNetwork {
+        SimplicityNetwork::LiquidTestnet
+    }
+
+    /// A PSET spending a single UTXO locked to `program`.
+    fn spending_pst(program: &Program) -> Result<PartiallySignedTransaction> {
+        let mut pst = PartiallySignedTransaction::new_v2();
+        let mut input = Input::from_prevout(OutPoint::new(Txid::from_slice(&[1; 32])?, 0));
+        input.witness_utxo = Some(TxOut {
+            script_pubkey: program.get_script_pubkey(&network()),
+            ..TxOut::default()
+        });
+        pst.add_input(input);
+        Ok(pst)
+    }
+
+    fn signature_witness(
+        keypair: &secp256k1::Keypair,
+        message: secp256k1::Message,
+    ) -> WitnessValues {
+        WitnessValues::from(HashMap::from([(
+            WitnessName::from_str_unchecked("USER_SIGNATURE"),
+            HlValue::byte_array(keypair.sign_schnorr(message).serialize()),
+        )]))
+    }
+
+    #[test]
+    fn test_compute_sighash_all_verifies_in_program() -> Result<()> {
+        let keypair = secp256k1::Keypair::from_secret_key(
+            secp256k1::SECP256K1,
+            &secp256k1::SecretKey::from_slice(&[1; 32])?,
+        );
+        let program = Program::new(
+            SIGNATURE_CHECK_SOURCE,
+            Box::new(SignatureCheckArguments(keypair.x_only_public_key().0)),
+        );
+        let pst = spending_pst(&program)?;
+
+        let sighash = compute_sighash_all(&program, &pst, 0, &network())?;
+
+        assert!(
+            program
+                .execute(&pst, &signature_witness(&keypair, sighash), 0, &network())
+                .is_ok()
+        );
+        assert!(
+            program
+                .execute(
+                    &pst,
+                    &signature_witness(&keypair, secp256k1::Message::from_digest([0; 32])),
+                    0,
+                    &network(),
+                )
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_env_rejects_missing_utxos() -> Result<()> {
+        let program = Program::new(
+            SIGNATURE_CHECK_SOURCE,
+            Box::new(SignatureCheckArguments(unspendable_internal_key())),
+        );
+
+        let mut pst = PartiallySignedTransaction::new_v2();
+        pst.add_input(Input::from_prevout(OutPoint::new(
+            Txid::from_slice(&[1; 32])?,
+            0,
+        )));
+        assert!(matches!(
+            get_env(&program, &pst, 0, &network()),
+            Err(ProgramError::NoUtxosProvided)
+        ));
+
+        let mut pst = spending_pst(&program)?;
+        let mut input = Input::from_prevout(OutPoint::new(Txid::from_slice(&[1; 32])?, 1));
+        input.witness_utxo = Some(TxOut::default());
+        pst.add_input(input);
+        assert!(matches!(
+            get_env(&program, &pst, 0, &network()),
+            Err(ProgramError::EmptyUtxoScript { utxo_index: 1 })
+        ));
+
+        assert!(get_env(&program, &spending_pst(&program)?, 0, &network()).is_ok());
+        assert!(matches!(
+            get_env(&program, &spending_pst(&program)?, 1, &network()),
+            Err(ProgramError::Simplex(
+                simplex::program::ProgramError::UtxoIndexOutOfBounds {
+                    input_index: 1,
+                    utxo_count: 1,
+                }
+            ))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_covenant_self_outputs_catches_misdirected_change() -> Result<()> {
+        let covenant_script = Script::new_op_return(b"covenant");
+
+        let mut pst = PartiallySignedTransaction::new_v2();
+        pst.add_input(Input::from_prevout(OutPoint::new(
+            Txid::from_slice(&[1; 32])?,
+            0,
+        )));
+        pst.add_output(Output::new_explicit(
+            covenant_script.clone(),
+            500,
+            AssetId::default(),
+            None,
+        ));
+        pst.add_output(Output::new_explicit(
+            Script::new_op_return(b"typo"),
+            500,
+            AssetId::default(),
+            None,
+        ));
+        let tx = pst.extract_tx()?;
+
+        assert!(verify_covenant_self_outputs(&tx, &covenant_script, &[0]).is_ok());
+        assert!(matches!(
+            verify_covenant_self_outputs(&tx, &covenant_script, &[0, 1]),
+            Err(ProgramError::CovenantOutputMisdirected {
+                output_index: 1,
+                ..
+            })
+        ));
+        assert!(matches!(
+            verify_covenant_self_outputs(&tx, &covenant_script, &[2]),
+            Err(ProgramError::OutputIndexOutOfBounds {
+                output_index: 2,
+                output_count: 2,
+            })
+        ));
+
+        Ok(())
+    }
+}
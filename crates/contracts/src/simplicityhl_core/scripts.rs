@@ -1,3 +1,6 @@
+use std::fmt::Write as _;
+use std::str::FromStr;
+
 use simplex::simplicityhl::ast::ElementsJetHinter;
 use simplex::simplicityhl::elements::{Address, AddressParams, Script, taproot};
 
@@ -6,7 +9,7 @@ use simplex::simplicityhl::simplicity::bitcoin::{XOnlyPublicKey, secp256k1};
 use simplex::simplicityhl::simplicity::hashes::{Hash, HashEngine, sha256};
 use simplex::simplicityhl::{Arguments, CompiledProgram};
 
-use super::error::ProgramError;
+use super::error::{AddressError, ProgramError};
 
 /// Compile `SimplicityHL` source into a Simplicity program with debug symbols.
 ///
@@ -35,6 +38,61 @@ pub fn create_p2tr_address(
     )
 }
 
+/// A Liquid address that has been checked against the expected network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedAddress {
+    pub address: Address,
+    /// Blinding public key of a confidential address, `None` otherwise.
+    pub blinding_pubkey: Option<secp256k1::PublicKey>,
+}
+
+impl ParsedAddress {
+    /// Whether outputs paying this address should be blinded.
+    #[must_use]
+    pub const fn is_confidential(&self) -> bool {
+        self.blinding_pubkey.is_some()
+    }
+}
+
+/// Parse a confidential or unconfidential Liquid address and check that it
+/// belongs to the network described by `params`.
+///
+/// # Errors
+/// Returns an error if the string is not a valid address or was encoded for
+/// another network.
+pub fn parse_liquid_address(
+    address: &str,
+    params: &'static AddressParams,
+) -> Result<ParsedAddress, AddressError> {
+    let address = Address::from_str(address)?;
+
+    if address.params != params {
+        return Err(AddressError::NetworkMismatch {
+            address: address.to_string(),
+        });
+    }
+
+    Ok(ParsedAddress {
+        blinding_pubkey: address.blinding_pubkey,
+        address,
+    })
+}
+
+/// Render a short fingerprint of a program CMR: the first 4 bytes as
+/// lowercase hex.
+///
+/// Two parties can read this aloud to confirm they derived the same
+/// contract without comparing full addresses.
+#[must_use]
+pub fn cmr_fingerprint(cmr: Cmr) -> String {
+    cmr.as_ref()[..4]
+        .iter()
+        .fold(String::with_capacity(8), |mut fingerprint, byte| {
+            let _ = write!(fingerprint, "{byte:02x}");
+            fingerprint
+        })
+}
+
 /// Return the version of Simplicity leaves inside a tap tree.
 #[must_use]
 pub fn simplicity_leaf_version() -> taproot::LeafVersion {
@@ -55,6 +113,24 @@ pub fn unspendable_internal_key() -> XOnlyPublicKey {
     .expect("key is valid")
 }
 
+/// Derive another unspendable internal key by adding `tweak * G` to the
+/// BIP-0341 key from [`unspendable_internal_key`].
+///
+/// Nobody knows a private key for the result either, and a fresh `tweak`
+/// keeps addresses from being linked to each other. Publishing `tweak` lets
+/// anyone check that the key is unspendable.
+///
+/// Returns `None` if `tweak` is not a valid secp256k1 scalar.
+#[must_use]
+pub fn tweaked_unspendable_key(tweak: [u8; 32]) -> Option<XOnlyPublicKey> {
+    let tweak = secp256k1::Scalar::from_be_bytes(tweak).ok()?;
+
+    unspendable_internal_key()
+        .add_tweak(secp256k1::SECP256K1, &tweak)
+        .ok()
+        .map(|(key, _)| key)
+}
+
 /// Create a SHA256 context, initialized with a "`TapData`" tag and data
 ///
 /// Based on the C implementation of the `tapdata_init` jet:
@@ -146,4 +222,76 @@ mod tests {
             "a33ad504fd45357a3909bf9dea8ce4aca38fe6e7d9c9d3e9e01211408990123f"
         );
     }
+
+    #[test]
+    fn test_tweaked_unspendable_key() {
+        assert_eq!(
+            tweaked_unspendable_key([0; 32]),
+            Some(unspendable_internal_key())
+        );
+
+        let tweaked = tweaked_unspendable_key([1; 32]).expect("valid tweak");
+        assert_ne!(tweaked, unspendable_internal_key());
+        assert_eq!(tweaked_unspendable_key([1; 32]), Some(tweaked));
+
+        assert_eq!(tweaked_unspendable_key([0xff; 32]), None);
+    }
+
+    #[test]
+    fn test_cmr_fingerprint_is_stable() {
+        let cmr = load_program("fn main() {}", Arguments::default())
+            .expect("program should compile")
+            .commit()
+            .cmr();
+
+        assert_eq!(cmr_fingerprint(cmr), "c40a1026");
+    }
+
+    #[test]
+    fn test_parse_liquid_address_detects_confidentiality() {
+        let cmr = load_program("fn main() {}", Arguments::default())
+            .expect("program should compile")
+            .commit()
+            .cmr();
+        let params = &AddressParams::LIQUID_TESTNET;
+        let address = create_p2tr_address(cmr, &unspendable_internal_key(), params);
+
+        let blinding_key = secp256k1::PublicKey::from_secret_key(
+            secp256k1::SECP256K1,
+            &secp256k1::SecretKey::from_slice(&[3u8; 32]).expect("valid secret key"),
+        );
+        let confidential_address = address.to_confidential(blinding_key);
+
+        let parsed = parse_liquid_address(&address.to_string(), params)
+            .expect("unconfidential address should parse");
+        assert!(!parsed.is_confidential());
+        assert_eq!(parsed.address, address);
+
+        let parsed = parse_liquid_address(&confidential_address.to_string(), params)
+            .expect("confidential address should parse");
+        assert!(parsed.is_confidential());
+        assert_eq!(parsed.blinding_pubkey, Some(blinding_key));
+    }
+
+    #[test]
+    fn test_parse_liquid_address_rejects_other_network() {
+        let cmr = load_program("fn main() {}", Arguments::default())
+            .expect("program should compile")
+            .commit()
+            .cmr();
+        let address = create_p2tr_address(
+            cmr,
+            &unspendable_internal_key(),
+            &AddressParams::LIQUID_TESTNET,
+        );
+
+        assert!(matches!(
+            parse_liquid_address(&address.to_string(), &AddressParams::LIQUID),
+            Err(AddressError::NetworkMismatch { .. })
+        ));
+        assert!(matches!(
+            parse_liquid_address("not an address", &AddressParams::LIQUID),
+            Err(AddressError::Parsing(_))
+        ));
+    }
 }
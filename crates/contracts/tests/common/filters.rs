@@ -1,5 +1,7 @@
 #![allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
 
+use std::collections::HashMap;
+
 use simplex::signer::Signer;
 use simplex::simplicityhl::elements::{AssetId, Script};
 use simplex::transaction::UTXO;
@@ -151,3 +153,73 @@ pub fn assert_covenant_utxo(
     assert_has_utxo_by_asset_and_amount(&utxos, asset_id, amount);
     Ok(())
 }
+
+/// Covenant UTXOs grouped by explicit asset. Blinded outputs (such as the
+/// options reissuance tokens) are kept apart, since their asset cannot be
+/// read without the blinding key.
+#[derive(Default)]
+pub struct CovenantUtxos {
+    pub explicit: HashMap<AssetId, Vec<UTXO>>,
+    pub confidential: Vec<UTXO>,
+}
+
+impl CovenantUtxos {
+    #[must_use]
+    pub fn from_utxos(utxos: Vec<UTXO>) -> Self {
+        let mut covenant_utxos = Self::default();
+        for utxo in utxos {
+            match utxo.txout.asset.explicit() {
+                Some(asset_id) => covenant_utxos
+                    .explicit
+                    .entry(asset_id)
+                    .or_default()
+                    .push(utxo),
+                None => covenant_utxos.confidential.push(utxo),
+            }
+        }
+        covenant_utxos
+    }
+
+    /// Explicit UTXOs holding `asset_id`.
+    #[must_use]
+    pub fn by_asset(&self, asset_id: AssetId) -> &[UTXO] {
+        self.explicit.get(&asset_id).map_or(&[], Vec::as_slice)
+    }
+
+    /// The explicit UTXO holding exactly `amount` of `asset_id`, failing with
+    /// `missing_utxo_message` if absent.
+    pub fn require(
+        &self,
+        asset_id: AssetId,
+        amount: u64,
+        missing_utxo_message: &str,
+    ) -> anyhow::Result<UTXO> {
+        require_utxo_by_asset_and_amount(
+            self.by_asset(asset_id),
+            asset_id,
+            amount,
+            missing_utxo_message,
+        )
+    }
+
+    /// Sum of the explicit amounts held in `asset_id`.
+    #[must_use]
+    pub fn balance(&self, asset_id: AssetId) -> u64 {
+        self.by_asset(asset_id)
+            .iter()
+            .filter_map(|utxo| utxo.txout.value.explicit())
+            .sum()
+    }
+}
+
+/// Fetch every UTXO locked at `script_pubkey` and classify it by asset, so
+/// builders can pick covenant inputs without knowing their outpoints.
+pub fn scan_covenant_utxos(
+    context: &simplex::TestContext,
+    script_pubkey: &Script,
+) -> anyhow::Result<CovenantUtxos> {
+    let utxos = context
+        .get_default_provider()
+        .fetch_scripthash_utxos(script_pubkey)?;
+    Ok(CovenantUtxos::from_utxos(utxos))
+}
@@ -6,11 +6,13 @@ use crate::common::filters::{
 };
 use simplex::provider::SimplicityNetwork;
 use simplex::signer::Signer;
-use simplex::simplicityhl::elements::{AssetId, Txid};
+use simplex::simplicityhl::elements::{AssetId, Script, Txid};
 use simplex::transaction::{
     FinalTransaction, PartialInput, PartialOutput, RequiredSignature, UTXO,
 };
 
+use contracts::simplicityhl_core::runner::verify_covenant_self_outputs;
+
 pub fn finalize_and_broadcast(
     context: &simplex::TestContext,
     ft: &FinalTransaction,
@@ -22,6 +24,21 @@ pub fn finalize_and_broadcast(
     Ok(txid.txid())
 }
 
+/// Like [`finalize_and_broadcast`], but checks covenant self-outputs first.
+///
+/// The outputs at `covenant_outputs` must pay back to `covenant_script`, so a
+/// misdirected change output fails here instead of inside the covenant program.
+pub fn finalize_and_broadcast_to_covenant(
+    context: &simplex::TestContext,
+    ft: &FinalTransaction,
+    covenant_script: &Script,
+    covenant_outputs: &[usize],
+) -> anyhow::Result<Txid> {
+    let (pst, _) = ft.extract_pst();
+    verify_covenant_self_outputs(&pst.extract_tx()?, covenant_script, covenant_outputs)?;
+    finalize_and_broadcast(context, ft)
+}
+
 pub fn ensure_exact_signer_utxo(
     context: &simplex::TestContext,
     asset_id: AssetId,
@@ -1,23 +1,21 @@
 #![allow(clippy::missing_errors_doc, clippy::missing_panics_doc)]
 
 use crate::common::filters::{
-    AmountFilter, filter_signer_utxos_by_asset_and_amount, require_covenant_utxo,
+    AmountFilter, filter_signer_utxos_by_asset_and_amount, scan_covenant_utxos,
 };
 use crate::common::issuance::issue_asset;
 use crate::common::offset_timestamp;
 use crate::common::signer::{
-    ensure_exact_signer_utxo, finalize_and_broadcast, get_lbtc_utxo, split_first_signer_utxo,
+    ensure_exact_signer_utxo, finalize_and_broadcast, finalize_and_broadcast_to_covenant,
+    get_lbtc_utxo, split_first_signer_utxo,
 };
 
-use std::collections::HashMap;
-
 use simplex::program::WitnessTrait;
-use simplex::signer::SignerTrait;
+use simplex::simplicityhl::WitnessValues;
 use simplex::simplicityhl::elements::Txid;
 use simplex::simplicityhl::elements::pset::PartiallySignedTransaction;
-use simplex::simplicityhl::str::WitnessName;
-use simplex::simplicityhl::value::ValueConstructible;
-use simplex::simplicityhl::{Value, WitnessValues};
+use simplex::simplicityhl::simplicity::bitcoin::key::Keypair;
+use simplex::simplicityhl::simplicity::bitcoin::secp256k1::SECP256K1;
 use simplex::transaction::{
     FinalTransaction, PartialInput, PartialOutput, ProgramInput, RequiredSignature, UTXO,
 };
@@ -30,13 +28,24 @@ pub const DEPOSIT_LBTC_AMOUNT: u64 = 1_000;
 pub const EXPECTED_PREMIUM_AMOUNT: u64 = 10_000;
 pub const EXPECTED_SETTLEMENT_AMOUNT: u64 = 10_000;
 
-/// Standard partial exercise of the offer: 600 of 1000 collateral for 6000
-/// settlement, with 6000 premium released and change locked back.
+/// Per-contract terms of the standard offer, derived the same way as in
+/// [`build_option_offer_program`].
+const STANDARD_PER_PARAMS: (Option<u64>, Option<u64>) = OptionOffer::calculate_per_params(
+    DEPOSIT_LBTC_AMOUNT,
+    EXPECTED_SETTLEMENT_AMOUNT,
+    EXPECTED_PREMIUM_AMOUNT,
+);
+
+/// Standard partial exercise of the offer: 600 of 1000 collateral, with the
+/// premium and settlement amounts following from the per-contract terms and
+/// change locked back.
 pub const PARTIAL_COLLATERAL_AMOUNT: u64 = 600;
 pub const REMAINING_COLLATERAL_AMOUNT: u64 = DEPOSIT_LBTC_AMOUNT - PARTIAL_COLLATERAL_AMOUNT;
-pub const EXERCISED_PREMIUM_AMOUNT: u64 = 6_000;
+pub const EXERCISED_PREMIUM_AMOUNT: u64 =
+    PARTIAL_COLLATERAL_AMOUNT * STANDARD_PER_PARAMS.1.unwrap();
 pub const REMAINING_PREMIUM_AMOUNT: u64 = EXPECTED_PREMIUM_AMOUNT - EXERCISED_PREMIUM_AMOUNT;
-pub const EXERCISED_SETTLEMENT_AMOUNT: u64 = 6_000;
+pub const EXERCISED_SETTLEMENT_AMOUNT: u64 =
+    PARTIAL_COLLATERAL_AMOUNT * STANDARD_PER_PARAMS.0.unwrap();
 
 pub fn prepare_option_offer(
     context: &simplex::TestContext,
@@ -209,24 +218,20 @@ pub fn offer_program_input(option_offer: &OptionOffer, branch: OptionOfferBranch
     )
 }
 
-/// Fetch the covenant UTXOs holding the offered collateral and premium.
+/// Scan the covenant for the UTXOs holding the offered collateral and premium.
 pub fn require_offer_utxos(
     context: &simplex::TestContext,
     option_offer: &OptionOffer,
     collateral_amount: u64,
     premium_amount: u64,
 ) -> anyhow::Result<[UTXO; 2]> {
-    let script_pubkey = option_offer.get_script_pubkey();
-    let collateral = require_covenant_utxo(
-        context,
-        &script_pubkey,
+    let covenant_utxos = scan_covenant_utxos(context, &option_offer.get_script_pubkey())?;
+    let collateral = covenant_utxos.require(
         option_offer.parameters.collateral_asset_id,
         collateral_amount,
         "missing collateral covenant utxo",
     )?;
-    let premium = require_covenant_utxo(
-        context,
-        &script_pubkey,
+    let premium = covenant_utxos.require(
         option_offer.parameters.premium_asset_id,
         premium_amount,
         "missing premium covenant utxo",
@@ -242,6 +247,20 @@ pub fn exercise_offer_partially(
     option_offer: &OptionOffer,
 ) -> anyhow::Result<Txid> {
     let receiver_script_pubkey = context.get_default_signer().get_address().script_pubkey();
+    anyhow::ensure!(
+        option_offer
+            .parameters
+            .required_settlement(PARTIAL_COLLATERAL_AMOUNT)
+            == Some(EXERCISED_SETTLEMENT_AMOUNT),
+        "offer terms do not match the standard partial settlement amount"
+    );
+    anyhow::ensure!(
+        option_offer
+            .parameters
+            .required_premium(PARTIAL_COLLATERAL_AMOUNT)
+            == Some(EXERCISED_PREMIUM_AMOUNT),
+        "offer terms do not match the standard partial premium amount"
+    );
     let settlement_input = ensure_exact_signer_utxo(
         context,
         option_offer.parameters.settlement_asset_id,
@@ -297,32 +316,21 @@ pub fn exercise_offer_partially(
         option_offer.parameters.premium_asset_id,
     ));
 
-    finalize_and_broadcast(context, &ft)
+    finalize_and_broadcast_to_covenant(context, &ft, &option_offer.get_script_pubkey(), &[0, 1, 2])
 }
 
-/// Build the witness for an offer branch with the user's `SIGHASH_ALL`
-/// signature filled in.
+/// Build the witness for an offer branch, signed by the default signer
+/// through [`OptionOffer::get_signed_witness`].
 pub fn witness_with_user_sighash(
     context: &simplex::TestContext,
     option_offer: &OptionOffer,
     branch: OptionOfferBranch,
     pst: &PartiallySignedTransaction,
 ) -> anyhow::Result<WitnessValues> {
-    let signature = context.get_default_signer().sign_program(
-        pst,
-        option_offer.get_program(),
-        0,
-        context.get_network(),
-    )?;
-
-    let mut signed_witness = HashMap::new();
-    for (name, value) in OptionOffer::get_witness(branch).build_witness().iter() {
-        signed_witness.insert(name.clone(), value.clone());
-    }
-    signed_witness.insert(
-        WitnessName::from_str_unchecked("USER_SIGHASH_ALL"),
-        Value::byte_array(signature.serialize()),
-    );
+    let private_key = context.get_default_signer().get_private_key();
+    let user_keypair = Keypair::from_secret_key(SECP256K1, &private_key.inner);
 
-    Ok(WitnessValues::from(signed_witness))
+    Ok(option_offer
+        .get_signed_witness(branch, pst, 0, &user_keypair)?
+        .build_witness())
 }
@@ -7,28 +7,30 @@
 use std::collections::HashMap;
 
 use crate::common::filters::{
-    AmountFilter, filter_signer_utxos_by_asset_and_amount, require_covenant_utxo,
+    AmountFilter, filter_signer_utxos_by_asset_and_amount, scan_covenant_utxos,
 };
 use crate::common::issuance::issue_asset;
 use crate::common::signer::{
-    ensure_exact_signer_utxo, finalize_and_broadcast, get_lbtc_utxo, split_first_signer_utxo,
+    ensure_exact_signer_utxo, finalize_and_broadcast_to_covenant, get_lbtc_utxo,
+    split_first_signer_utxo,
 };
 use crate::common::{locked_input, locktime_from, offset_timestamp};
 
 use anyhow::Context;
 use contracts::programs::options::{
-    Options, OptionsBranch, OptionsFundingBlinders, OptionsParameters,
+    Options, OptionsBranch, OptionsCovenantFunding, OptionsFundingBlinders, OptionsParameters,
+    deterministic_blinding_rng,
 };
 use contracts::programs::program::SimplexProgram;
+use contracts::simplicityhl_core::runner::verify_covenant_self_outputs;
 
 use simplex::program::{ProgramTrait, WitnessTrait};
 use simplex::signer::SignerTrait;
-use simplex::simplicityhl::elements::secp256k1_zkp::Tweak;
 use simplex::simplicityhl::elements::{
     AssetId, ContractHash, EcdsaSighashType, OutPoint, Script, TxOutSecrets, Txid,
     confidential::{AssetBlindingFactor, ValueBlindingFactor},
     pset::PartiallySignedTransaction,
-    secp256k1_zkp::{Secp256k1, rand::thread_rng},
+    secp256k1_zkp::Secp256k1,
 };
 use simplex::simplicityhl::simplicity::hashes::Hash;
 use simplex::transaction::partial_input::IssuanceInput;
@@ -38,6 +40,9 @@ use simplex::transaction::{
 
 const OPTION_ISSUANCE_CONTRACT_HASH: [u8; 32] = [1; 32];
 const GRANTOR_ISSUANCE_CONTRACT_HASH: [u8; 32] = [2; 32];
+/// Seed for the blinding RNG of the creation and funding transactions, so
+/// their blinded outputs only depend on the spent outpoints.
+const BLINDING_SEED: [u8; 32] = [3; 32];
 
 /// Standard options contract sizing shared by the regtest scenarios.
 pub const TOTAL_COLLATERAL_AMOUNT: u64 = 1_000;
@@ -270,8 +275,12 @@ pub fn create_options(
             ),
         ),
     ]);
-    pst.blind_last(&mut thread_rng(), &Secp256k1::new(), &input_secrets)
-        .context("create_options blind outputs")?;
+    pst.blind_last(
+        &mut deterministic_blinding_rng(BLINDING_SEED, prepared.option_issuance_source.outpoint),
+        &Secp256k1::new(),
+        &input_secrets,
+    )
+    .context("create_options blind outputs")?;
 
     for input_index in 0..pst.inputs().len() {
         let (public_key, signature) = signer
@@ -354,10 +363,6 @@ pub fn fund_options(
         .grantor_reissuance_token
         .secrets
         .ok_or_else(|| anyhow::anyhow!("missing grantor reissuance input secrets"))?;
-    let option_input_abf = tweak_to_bytes(option_input_secrets.asset_bf.into_inner());
-    let option_input_vbf = tweak_to_bytes(option_input_secrets.value_bf.into_inner());
-    let grantor_input_abf = tweak_to_bytes(grantor_input_secrets.asset_bf.into_inner());
-    let grantor_input_vbf = tweak_to_bytes(grantor_input_secrets.value_bf.into_inner());
 
     let signer = context.get_default_signer();
     let collateral_input = ensure_exact_signer_utxo(
@@ -380,7 +385,7 @@ pub fn fund_options(
     let mut option_input = PartialInput::new(created.option_reissuance_token.clone()).to_input();
     let option_issuance =
         IssuanceInput::new_reissuance(contract_count, created.option_issuance_entropy).to_input();
-    option_input.issuance_blinding_nonce = Some(Tweak::from_inner(option_input_abf)?);
+    option_input.issuance_blinding_nonce = Some(option_input_secrets.asset_bf.into_inner());
     option_input.issuance_value_amount = option_issuance.issuance_value_amount;
     option_input.issuance_asset_entropy = option_issuance.issuance_asset_entropy;
     option_input.blinded_issuance = option_issuance.blinded_issuance;
@@ -388,7 +393,7 @@ pub fn fund_options(
     let mut grantor_input = PartialInput::new(created.grantor_reissuance_token.clone()).to_input();
     let grantor_issuance =
         IssuanceInput::new_reissuance(contract_count, created.grantor_issuance_entropy).to_input();
-    grantor_input.issuance_blinding_nonce = Some(Tweak::from_inner(grantor_input_abf)?);
+    grantor_input.issuance_blinding_nonce = Some(grantor_input_secrets.asset_bf.into_inner());
     grantor_input.issuance_value_amount = grantor_issuance.issuance_value_amount;
     grantor_input.issuance_asset_entropy = grantor_issuance.issuance_asset_entropy;
     grantor_input.blinded_issuance = grantor_issuance.blinded_issuance;
@@ -463,12 +468,22 @@ pub fn fund_options(
         (2_usize, explicit_txout_secrets(&collateral_input)),
         (3_usize, explicit_txout_secrets(&fee_input)),
     ]);
-    pst.blind_last(&mut thread_rng(), &Secp256k1::new(), &input_secrets)
-        .context("fund_options blind outputs")?;
+    pst.blind_last(
+        &mut deterministic_blinding_rng(BLINDING_SEED, created.option_reissuance_token.outpoint),
+        &Secp256k1::new(),
+        &input_secrets,
+    )
+    .context("fund_options blind outputs")?;
 
     let blinded_tx = pst
         .extract_tx()
         .context("fund_options extract blinded tx")?;
+    verify_covenant_self_outputs(
+        &blinded_tx,
+        &created.options.get_script_pubkey(),
+        &[0, 1, 2],
+    )
+    .context("fund_options covenant outputs")?;
     let blinding_key = signer.get_blinding_private_key();
     let secp = Secp256k1::new();
     let option_output_secrets = blinded_tx.output[0]
@@ -479,16 +494,12 @@ pub fn fund_options(
         .context("fund_options unblind grantor reissuance output")?;
     let funding_witness = Options::get_witness(OptionsBranch::Fund {
         expected_settlement_amount,
-        blinders: OptionsFundingBlinders {
-            input_option_abf: option_input_abf,
-            input_option_vbf: option_input_vbf,
-            input_grantor_abf: grantor_input_abf,
-            input_grantor_vbf: grantor_input_vbf,
-            output_option_abf: tweak_to_bytes(option_output_secrets.asset_bf.into_inner()),
-            output_option_vbf: tweak_to_bytes(option_output_secrets.value_bf.into_inner()),
-            output_grantor_abf: tweak_to_bytes(grantor_output_secrets.asset_bf.into_inner()),
-            output_grantor_vbf: tweak_to_bytes(grantor_output_secrets.value_bf.into_inner()),
-        },
+        blinders: OptionsFundingBlinders::from_secrets(
+            &option_input_secrets,
+            &grantor_input_secrets,
+            &option_output_secrets,
+            &grantor_output_secrets,
+        ),
     })
     .build_witness();
 
@@ -582,20 +593,68 @@ pub fn options_program_input(options: &Options, branch: OptionsBranch) -> Progra
     )
 }
 
-/// Fetch the covenant UTXO holding the full locked collateral.
+/// Scan the covenant for the UTXO holding the full locked collateral.
 pub fn require_locked_collateral(
     context: &simplex::TestContext,
     funded: &FundedOptionsState,
 ) -> anyhow::Result<UTXO> {
-    require_covenant_utxo(
-        context,
-        &funded.options.get_script_pubkey(),
+    scan_covenant_utxos(context, &funded.options.get_script_pubkey())?.require(
         funded.options.parameters.collateral_asset_id,
         TOTAL_COLLATERAL_AMOUNT,
         "missing locked collateral covenant utxo",
     )
 }
 
+/// Scan the covenant for the UTXO holding the settlement locked by
+/// [`exercise_options_fully`].
+pub fn require_locked_settlement(
+    context: &simplex::TestContext,
+    funded: &FundedOptionsState,
+) -> anyhow::Result<UTXO> {
+    scan_covenant_utxos(context, &funded.options.get_script_pubkey())?.require(
+        funded.options.parameters.settlement_asset_id,
+        EXPECTED_SETTLEMENT_AMOUNT,
+        "missing locked settlement covenant utxo",
+    )
+}
+
+/// Scan the covenant of a funded contract and check that its holdings match
+/// the contract terms before building a spend against it.
+///
+/// The balances are checked with [`OptionsParameters::check_covenant_funding`];
+/// on top of that, both blinded reissuance tokens must still be locked at the
+/// outpoints the funding transaction created.
+pub fn assert_options_covenant_funded(
+    context: &simplex::TestContext,
+    funded: &FundedOptionsState,
+) -> anyhow::Result<OptionsCovenantFunding> {
+    let parameters = &funded.options.parameters;
+    let covenant_utxos = scan_covenant_utxos(context, &funded.options.get_script_pubkey())?;
+
+    let funding = parameters.check_covenant_funding(
+        covenant_utxos.balance(parameters.collateral_asset_id),
+        covenant_utxos.balance(parameters.settlement_asset_id),
+    )?;
+
+    for (name, token) in [
+        ("option", &funded.option_reissuance_token),
+        ("grantor", &funded.grantor_reissuance_token),
+    ] {
+        if !covenant_utxos
+            .confidential
+            .iter()
+            .any(|utxo| utxo.outpoint == token.outpoint)
+        {
+            return Err(anyhow::anyhow!(
+                "{name} reissuance token {} is no longer locked at the covenant",
+                token.outpoint
+            ));
+        }
+    }
+
+    Ok(funding)
+}
+
 /// Exercise the full collateral of a contract funded via
 /// [`setup_funded_options`].
 ///
@@ -656,7 +715,7 @@ pub fn exercise_options_fully(
         parameters.collateral_asset_id,
     ));
 
-    finalize_and_broadcast(context, &ft)
+    finalize_and_broadcast_to_covenant(context, &ft, &funded.options.get_script_pubkey(), &[1])
 }
 
 fn issuance_ids(
@@ -679,12 +738,6 @@ fn signer_script(context: &simplex::TestContext) -> Script {
     context.get_default_signer().get_address().script_pubkey()
 }
 
-fn tweak_to_bytes(tweak: Tweak) -> [u8; 32] {
-    let mut bytes = [0_u8; 32];
-    bytes.copy_from_slice(tweak.as_ref());
-    bytes
-}
-
 fn explicit_txout_secrets(utxo: &UTXO) -> TxOutSecrets {
     TxOutSecrets::new(
         utxo.asset(),
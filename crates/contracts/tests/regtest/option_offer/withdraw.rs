@@ -1,7 +1,9 @@
 use crate::common::filters::{
     assert_covenant_utxo, assert_has_utxo_by_asset_amount_and_script, require_covenant_utxo,
 };
-use crate::common::signer::{finalize_and_broadcast, get_lbtc_utxo};
+use crate::common::signer::{
+    finalize_and_broadcast, finalize_and_broadcast_to_covenant, get_lbtc_utxo,
+};
 use crate::program_builder::option_offer::{
     DEPOSIT_LBTC_AMOUNT, EXERCISED_SETTLEMENT_AMOUNT, EXPECTED_PREMIUM_AMOUNT,
     EXPECTED_SETTLEMENT_AMOUNT, REMAINING_COLLATERAL_AMOUNT, REMAINING_PREMIUM_AMOUNT,
@@ -9,31 +11,40 @@ use crate::program_builder::option_offer::{
     prepare_option_offer, setup_offer_with_premium, witness_with_user_sighash,
 };
 
-use simplex::program::{ProgramError, ProgramTrait};
+use simplex::program::{ProgramError, ProgramTrait, WitnessTrait};
+use simplex::signer::SignerTrait;
 use simplex::transaction::{FinalTransaction, PartialInput, PartialOutput, RequiredSignature};
 
-use contracts::programs::option_offer::{OptionOffer, OptionOfferBranch};
+use contracts::programs::option_offer::{OptionOffer, OptionOfferBranch, OptionOfferError};
 use contracts::programs::program::SimplexProgram;
 
-/// Build a withdraw transaction spending the settlement covenant UTXO left by
-/// [`exercise_offer_partially`] into the given outputs.
+/// Full withdrawal of the settlement locked by [`exercise_offer_partially`].
+const FULL_WITHDRAW_BRANCH: OptionOfferBranch = OptionOfferBranch::Withdraw {
+    amount: EXERCISED_SETTLEMENT_AMOUNT,
+    is_change_needed: false,
+};
+
+/// Build a withdraw transaction spending the covenant UTXO holding
+/// `settlement_amount` of the settlement asset into the given outputs.
 fn build_withdraw_ft(
     context: &simplex::TestContext,
     option_offer: &OptionOffer,
+    settlement_amount: u64,
+    branch: OptionOfferBranch,
     outputs: Vec<PartialOutput>,
 ) -> anyhow::Result<FinalTransaction> {
     let settlement_program_utxo = require_covenant_utxo(
         context,
         &option_offer.get_script_pubkey(),
         option_offer.parameters.settlement_asset_id,
-        EXERCISED_SETTLEMENT_AMOUNT,
+        settlement_amount,
         "missing settlement covenant utxo",
     )?;
 
     let mut ft = FinalTransaction::new();
     ft.add_program_input(
         PartialInput::new(settlement_program_utxo),
-        offer_program_input(option_offer, OptionOfferBranch::Withdraw),
+        offer_program_input(option_offer, branch),
         RequiredSignature::Witness("USER_SIGHASH_ALL".to_string()),
     );
     ft.add_input(
@@ -84,6 +95,8 @@ fn withdraw_option_offer(context: simplex::TestContext) -> anyhow::Result<()> {
     let withdraw_ft = build_withdraw_ft(
         &context,
         &option_offer,
+        EXERCISED_SETTLEMENT_AMOUNT,
+        FULL_WITHDRAW_BRANCH,
         vec![PartialOutput::new(
             receiver_script_pubkey.clone(),
             EXERCISED_SETTLEMENT_AMOUNT,
@@ -118,6 +131,83 @@ fn withdraw_option_offer(context: simplex::TestContext) -> anyhow::Result<()> {
     Ok(())
 }
 
+#[simplex::test]
+fn withdraw_option_offer_in_two_steps(context: simplex::TestContext) -> anyhow::Result<()> {
+    let signer = context.get_default_signer();
+
+    let option_offer = setup_offer_with_premium(&context, 1_000)?;
+    let _ = exercise_offer_partially(&context, &option_offer)?;
+
+    let script_pubkey = option_offer.get_script_pubkey();
+    let receiver_script_pubkey = signer.get_address().script_pubkey();
+    let settlement_asset_id = option_offer.parameters.settlement_asset_id;
+
+    let first_withdraw_amount = 2_000;
+    let remaining_settlement_amount = EXERCISED_SETTLEMENT_AMOUNT - first_withdraw_amount;
+
+    let first_withdraw_ft = build_withdraw_ft(
+        &context,
+        &option_offer,
+        EXERCISED_SETTLEMENT_AMOUNT,
+        OptionOfferBranch::Withdraw {
+            amount: first_withdraw_amount,
+            is_change_needed: true,
+        },
+        vec![
+            PartialOutput::new(
+                receiver_script_pubkey.clone(),
+                first_withdraw_amount,
+                settlement_asset_id,
+            ),
+            PartialOutput::new(
+                script_pubkey.clone(),
+                remaining_settlement_amount,
+                settlement_asset_id,
+            ),
+        ],
+    )?;
+    let first_withdraw_txid =
+        finalize_and_broadcast_to_covenant(&context, &first_withdraw_ft, &script_pubkey, &[1])?;
+
+    assert_covenant_utxo(
+        &context,
+        &script_pubkey,
+        settlement_asset_id,
+        remaining_settlement_amount,
+    )?;
+    assert_has_utxo_by_asset_amount_and_script(
+        &signer.get_utxos_txid(first_withdraw_txid)?,
+        settlement_asset_id,
+        first_withdraw_amount,
+        &receiver_script_pubkey,
+    );
+
+    let second_withdraw_ft = build_withdraw_ft(
+        &context,
+        &option_offer,
+        remaining_settlement_amount,
+        OptionOfferBranch::Withdraw {
+            amount: remaining_settlement_amount,
+            is_change_needed: false,
+        },
+        vec![PartialOutput::new(
+            receiver_script_pubkey.clone(),
+            remaining_settlement_amount,
+            settlement_asset_id,
+        )],
+    )?;
+    let second_withdraw_txid = finalize_and_broadcast(&context, &second_withdraw_ft)?;
+
+    assert_has_utxo_by_asset_amount_and_script(
+        &signer.get_utxos_txid(second_withdraw_txid)?,
+        settlement_asset_id,
+        remaining_settlement_amount,
+        &receiver_script_pubkey,
+    );
+
+    Ok(())
+}
+
 #[simplex::test]
 fn withdraw_option_offer_rejects_invalid_signature(
     context: simplex::TestContext,
@@ -150,6 +240,8 @@ fn withdraw_option_offer_rejects_invalid_signature(
     let withdraw_ft = build_withdraw_ft(
         &context,
         &option_offer,
+        EXERCISED_SETTLEMENT_AMOUNT,
+        FULL_WITHDRAW_BRANCH,
         vec![PartialOutput::new(
             signer.get_address().script_pubkey(),
             EXERCISED_SETTLEMENT_AMOUNT,
@@ -158,11 +250,23 @@ fn withdraw_option_offer_rejects_invalid_signature(
     )?;
 
     let (pst, _) = withdraw_ft.extract_pst();
-    let witness =
-        witness_with_user_sighash(&context, &option_offer, OptionOfferBranch::Withdraw, &pst)?;
+    let key_error = witness_with_user_sighash(&context, &option_offer, FULL_WITHDRAW_BRANCH, &pst)
+        .expect_err("the default signer does not hold the offer user key");
+    assert!(matches!(
+        key_error.downcast_ref::<OptionOfferError>(),
+        Some(OptionOfferError::UserKeyMismatch { expected, actual })
+            if **expected == option_offer.parameters.user_pubkey
+                && **actual == signer.get_schnorr_public_key()
+    ));
+
+    // Bypass the key check and sign with the default signer anyway.
+    let mut witness = OptionOffer::get_witness(FULL_WITHDRAW_BRANCH);
+    witness.user_sighash_all = signer
+        .sign_program(&pst, option_offer.get_program(), 0, context.get_network())?
+        .serialize();
     let program_error = option_offer
         .get_program()
-        .finalize(&pst, &witness, 0, context.get_network())
+        .finalize(&pst, &witness.build_witness(), 0, context.get_network())
         .expect_err("withdraw should reject a mismatched user signature");
     assert!(matches!(program_error, ProgramError::Pruning(_)));
 
@@ -186,6 +290,8 @@ fn withdraw_option_offer_rejects_partial_output_amount(
     let withdraw_ft = build_withdraw_ft(
         &context,
         &option_offer,
+        EXERCISED_SETTLEMENT_AMOUNT,
+        FULL_WITHDRAW_BRANCH,
         vec![
             PartialOutput::new(
                 receiver_script_pubkey.clone(),
@@ -201,8 +307,7 @@ fn withdraw_option_offer_rejects_partial_output_amount(
     )?;
 
     let (pst, _) = withdraw_ft.extract_pst();
-    let witness =
-        witness_with_user_sighash(&context, &option_offer, OptionOfferBranch::Withdraw, &pst)?;
+    let witness = witness_with_user_sighash(&context, &option_offer, FULL_WITHDRAW_BRANCH, &pst)?;
     let program_error = option_offer
         .get_program()
         .finalize(&pst, &witness, 0, context.get_network())
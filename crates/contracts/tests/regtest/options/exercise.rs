@@ -1,6 +1,8 @@
 use crate::common::filters::assert_covenant_utxo;
 use crate::common::filters::assert_has_utxo_by_asset_and_amount;
-use crate::common::signer::{ensure_exact_signer_utxo, finalize_and_broadcast, get_lbtc_utxo};
+use crate::common::signer::{
+    ensure_exact_signer_utxo, finalize_and_broadcast_to_covenant, get_lbtc_utxo,
+};
 use crate::common::{locked_input, locktime_from};
 use crate::program_builder::options::{
     CONTRACT_COUNT, EXPECTED_SETTLEMENT_AMOUNT, TOTAL_COLLATERAL_AMOUNT, exercise_options_fully,
@@ -148,7 +150,12 @@ fn exercise_options_with_change(context: simplex::TestContext) -> anyhow::Result
         parameters.collateral_asset_id,
     ));
 
-    let exercise_txid = finalize_and_broadcast(&context, &ft)?;
+    let exercise_txid = finalize_and_broadcast_to_covenant(
+        &context,
+        &ft,
+        &funded.options.get_script_pubkey(),
+        &[0, 2],
+    )?;
 
     let transaction = provider.fetch_transaction(&exercise_txid)?;
     assert_eq!(
@@ -1,5 +1,8 @@
 use crate::common::filters::{assert_covenant_utxo, assert_has_utxo_by_asset_and_amount};
-use crate::common::signer::{ensure_exact_signer_utxo, finalize_and_broadcast, get_lbtc_utxo};
+use crate::common::signer::{
+    ensure_exact_signer_utxo, finalize_and_broadcast, finalize_and_broadcast_to_covenant,
+    get_lbtc_utxo,
+};
 use crate::common::{locked_input, locktime_from};
 use crate::program_builder::options::{
     CONTRACT_COUNT, TOTAL_COLLATERAL_AMOUNT, options_program_input, require_locked_collateral,
@@ -145,7 +148,12 @@ fn expire_options_with_change(context: simplex::TestContext) -> anyhow::Result<(
         parameters.collateral_asset_id,
     ));
 
-    let expiry_txid = finalize_and_broadcast(&context, &ft)?;
+    let expiry_txid = finalize_and_broadcast_to_covenant(
+        &context,
+        &ft,
+        &funded.options.get_script_pubkey(),
+        &[0],
+    )?;
 
     let transaction = provider.fetch_transaction(&expiry_txid)?;
     assert_eq!(
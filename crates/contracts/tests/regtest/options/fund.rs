@@ -1,6 +1,8 @@
-use crate::common::filters::{assert_covenant_utxo, assert_has_utxo_by_asset_amount_and_script};
+use crate::common::filters::{
+    assert_covenant_utxo, assert_has_utxo_by_asset_amount_and_script, scan_covenant_utxos,
+};
 use crate::program_builder::options::{
-    CONTRACT_COUNT, TOTAL_COLLATERAL_AMOUNT, setup_funded_options,
+    CONTRACT_COUNT, TOTAL_COLLATERAL_AMOUNT, assert_options_covenant_funded, setup_funded_options,
 };
 
 use contracts::programs::program::SimplexProgram;
@@ -93,3 +95,53 @@ fn fund_options_contract(context: simplex::TestContext) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[simplex::test]
+fn scan_funded_options_covenant(context: simplex::TestContext) -> anyhow::Result<()> {
+    let funded = setup_funded_options(&context, -100, 1_000)?;
+    let parameters = &funded.options.parameters;
+
+    let covenant_utxos = scan_covenant_utxos(&context, &funded.options.get_script_pubkey())?;
+
+    let collateral_utxos = covenant_utxos.by_asset(parameters.collateral_asset_id);
+    assert_eq!(collateral_utxos.len(), 1);
+    assert_eq!(
+        collateral_utxos[0].outpoint,
+        funded.locked_collateral.outpoint
+    );
+    assert_eq!(
+        covenant_utxos.balance(parameters.collateral_asset_id),
+        TOTAL_COLLATERAL_AMOUNT
+    );
+
+    // Both reissuance tokens are blinded to the signer and cannot be
+    // classified by asset from chain data alone.
+    assert_eq!(covenant_utxos.explicit.len(), 1);
+    assert_eq!(covenant_utxos.confidential.len(), 2);
+    for token in [
+        &funded.option_reissuance_token,
+        &funded.grantor_reissuance_token,
+    ] {
+        assert!(
+            covenant_utxos
+                .confidential
+                .iter()
+                .any(|utxo| utxo.outpoint == token.outpoint)
+        );
+    }
+
+    Ok(())
+}
+
+#[simplex::test]
+fn assert_funded_options_holdings(context: simplex::TestContext) -> anyhow::Result<()> {
+    let funded = setup_funded_options(&context, -100, 1_000)?;
+
+    let funding = assert_options_covenant_funded(&context, &funded)?;
+
+    assert_eq!(funding.collateral_amount, TOTAL_COLLATERAL_AMOUNT);
+    assert_eq!(funding.settlement_amount, 0);
+    assert_eq!(funding.remaining_contracts, CONTRACT_COUNT);
+
+    Ok(())
+}
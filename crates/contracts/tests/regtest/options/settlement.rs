@@ -1,11 +1,12 @@
-use crate::common::filters::{
-    assert_covenant_utxo, assert_has_utxo_by_asset_and_amount, require_covenant_utxo,
+use crate::common::filters::{assert_covenant_utxo, assert_has_utxo_by_asset_and_amount};
+use crate::common::signer::{
+    ensure_exact_signer_utxo, finalize_and_broadcast, finalize_and_broadcast_to_covenant,
+    get_lbtc_utxo,
 };
-use crate::common::signer::{ensure_exact_signer_utxo, finalize_and_broadcast, get_lbtc_utxo};
 use crate::common::{locked_input, locktime_from};
 use crate::program_builder::options::{
     CONTRACT_COUNT, EXPECTED_SETTLEMENT_AMOUNT, exercise_options_fully, options_program_input,
-    setup_funded_options,
+    require_locked_settlement, setup_funded_options,
 };
 
 use contracts::programs::options::{Options, OptionsBranch};
@@ -25,13 +26,7 @@ fn settle_options(context: simplex::TestContext) -> anyhow::Result<()> {
 
     let parameters = &funded.options.parameters;
     let locktime = locktime_from(parameters.start_time)?;
-    let locked_settlement = require_covenant_utxo(
-        &context,
-        &funded.options.get_script_pubkey(),
-        parameters.settlement_asset_id,
-        EXPECTED_SETTLEMENT_AMOUNT,
-        "missing locked settlement covenant utxo",
-    )?;
+    let locked_settlement = require_locked_settlement(&context, &funded)?;
     let grantor_token_input =
         ensure_exact_signer_utxo(&context, parameters.grantor_token_asset, CONTRACT_COUNT)?;
 
@@ -116,13 +111,7 @@ fn settle_options_with_change(context: simplex::TestContext) -> anyhow::Result<(
 
     let parameters = &funded.options.parameters;
     let locktime = locktime_from(parameters.start_time)?;
-    let locked_settlement = require_covenant_utxo(
-        &context,
-        &funded.options.get_script_pubkey(),
-        parameters.settlement_asset_id,
-        EXPECTED_SETTLEMENT_AMOUNT,
-        "missing locked settlement covenant utxo",
-    )?;
+    let locked_settlement = require_locked_settlement(&context, &funded)?;
     let grantor_token_input = ensure_exact_signer_utxo(
         &context,
         parameters.grantor_token_asset,
@@ -166,7 +155,12 @@ fn settle_options_with_change(context: simplex::TestContext) -> anyhow::Result<(
         parameters.settlement_asset_id,
     ));
 
-    let settlement_txid = finalize_and_broadcast(&context, &settlement_ft)?;
+    let settlement_txid = finalize_and_broadcast_to_covenant(
+        &context,
+        &settlement_ft,
+        &funded.options.get_script_pubkey(),
+        &[0],
+    )?;
 
     let transaction = provider.fetch_transaction(&settlement_txid)?;
     assert_eq!(
@@ -206,13 +200,7 @@ fn settle_options_rejects_missing_locktime(context: simplex::TestContext) -> any
     let _ = exercise_options_fully(&context, &funded)?;
 
     let parameters = &funded.options.parameters;
-    let locked_settlement = require_covenant_utxo(
-        &context,
-        &funded.options.get_script_pubkey(),
-        parameters.settlement_asset_id,
-        EXPECTED_SETTLEMENT_AMOUNT,
-        "missing locked settlement covenant utxo",
-    )?;
+    let locked_settlement = require_locked_settlement(&context, &funded)?;
     let grantor_token_input =
         ensure_exact_signer_utxo(&context, parameters.grantor_token_asset, CONTRACT_COUNT)?;
 